@@ -0,0 +1,391 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use auto_team_totals::{ClubPoints, Competition, Event, MedalCount, ParseError, PointsSystemKind, Rules, ScoringSystem, Settings, Standings};
+use auto_team_totals::{awards_participation_points, display_points, matches_pattern, medal_ranks, medal_table_places, merge_suggestions, shared_ranks, subtotal_disciplines, PLACE_NAMES};
+
+const USAGE: &str = r#"Usage: auto_team_totals <COMMAND> <COMPETITION> [OPTIONS]
+
+Running without a command launches the graphical interface.
+
+Commands:
+  list-events    List the events found for the competition
+  tabulate       Tabulate the team totals and print them
+  export         Tabulate the team totals and write the output files
+  help           Print this message
+
+Competition:
+  The name of the competition, or its absolute path when --absolute-path is given
+  or use_event_name_for_results_path is disabled in settings.toml.
+
+Options:
+  --include <PATTERN>         Only include events matching PATTERN (repeatable)
+  --exclude <PATTERN>         Exclude events matching PATTERN (repeatable)
+  --absolute-path             Treat COMPETITION as an absolute path
+  --no-ijs                    Exclude IJS events
+  --no-60                     Exclude 6.0 events
+  --points <LIST>             Points for each placement, e.g. 3,2,1
+  --spreadsheet <FILE>        Use a scoring system spreadsheet
+  --formula <EXPR>            Score with a formula of rank and participants,
+                              e.g. "max(0, participants - rank + 1)"
+  --club-directory <FILE>     Check clubs against a club directory (CSV or spreadsheet)
+  --roster <FILE>             Take each skater's club from a roster (CSV or spreadsheet)
+  --output-directory <DIR>    Directory to write output files to
+  --set <KEY>=<VALUE>         Override any setting from settings.toml (repeatable)
+
+Patterns are matched against the whole event name, ignore case and may use * and ?
+as wildcards. Overrides only apply to this run and are never written to settings.toml."#;
+
+enum Command {
+    ListEvents,
+    Tabulate,
+    Export,
+}
+
+struct CliOptions {
+    command: Command,
+    competition: String,
+    include_patterns: Vec<String>,
+    exclude_patterns: Vec<String>,
+    settings: Settings,
+}
+
+// Run the command line interface and return the process exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    let options = match parse_args(args, Settings::read()) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return 2;
+        }
+    };
+
+    let competition = match open_competition(&options) {
+        Ok(competition) => competition,
+        Err(err) => {
+            eprintln!("{}", err);
+            return 1;
+        }
+    };
+
+    match options.command {
+        Command::ListEvents => {
+            list_events(competition.events());
+            0
+        }
+        Command::Tabulate => {
+            match tabulate(&competition, &options.settings) {
+                Some(standings) => {
+                    print_standings(&standings, &options.settings);
+                    0
+                }
+                None => 1,
+            }
+        }
+        Command::Export => {
+            match tabulate(&competition, &options.settings) {
+                Some(standings) => {
+                    standings.output_files(&options.settings, competition.name());
+                    print_standings(&standings, &options.settings);
+                    println!("Output written to {}", options.settings.output_directory);
+                    0
+                }
+                None => 1,
+            }
+        }
+    }
+}
+
+// Parse the command line arguments. Returns None if help was requested.
+fn parse_args(args: Vec<String>, mut settings: Settings) -> Result<Option<CliOptions>, String> {
+    let mut args = args.into_iter();
+
+    let command = match args.next().as_deref() {
+        Some("list-events") => Command::ListEvents,
+        Some("tabulate") => Command::Tabulate,
+        Some("export") => Command::Export,
+        Some("help") | Some("--help") | Some("-h") | None => return Ok(None),
+        Some(command) => return Err(format!("Unknown command \"{}\"", command)),
+    };
+
+    let mut competition = None;
+    let mut include_patterns = vec![];
+    let mut exclude_patterns = vec![];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(None),
+            "--include" => include_patterns.push(next_value(&mut args, &arg)?),
+            "--exclude" => exclude_patterns.push(next_value(&mut args, &arg)?),
+            "--absolute-path" => settings.use_event_name_for_results_path = false,
+            "--no-ijs" => settings.include_ijs = false,
+            "--no-60" => settings.include_60 = false,
+            "--points" => {
+                let value = next_value(&mut args, &arg)?;
+                settings.default_points_system = parse_points(&value)?;
+                settings.points_system = PointsSystemKind::FlatTable;
+            }
+            "--spreadsheet" => {
+                settings.scoring_system_file_name = Some(next_value(&mut args, &arg)?.replace("\\", "/"));
+                settings.points_system = PointsSystemKind::FieldSizeChart;
+            }
+            "--formula" => {
+                settings.points_formula = next_value(&mut args, &arg)?;
+                settings.points_system = PointsSystemKind::Formula;
+            }
+            "--club-directory" => settings.club_directory_file_name = Some(next_value(&mut args, &arg)?.replace("\\", "/")),
+            "--roster" => settings.roster_file_name = Some(next_value(&mut args, &arg)?.replace("\\", "/")),
+            "--output-directory" => settings.output_directory = next_value(&mut args, &arg)?.replace("\\", "/"),
+            "--set" => {
+                let value = next_value(&mut args, &arg)?;
+                let (key, value) = match value.split_once('=') {
+                    Some((key, value)) => (key.trim(), value.trim()),
+                    None => return Err(format!("Expected KEY=VALUE after --set, found \"{}\"", value)),
+                };
+                settings = override_setting(settings, key, value)?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option \"{}\"", arg)),
+            _ => {
+                if competition.is_some() {
+                    return Err(format!("Unexpected argument \"{}\"", arg));
+                }
+                competition = Some(arg);
+            }
+        }
+    }
+
+    let competition = match competition {
+        Some(competition) => competition,
+        None => return Err(String::from("No competition given")),
+    };
+
+    settings.check_points_formulas()?;
+
+    Ok(Some(CliOptions {
+        command,
+        competition,
+        include_patterns,
+        exclude_patterns,
+        settings,
+    }))
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, option: &str) -> Result<String, String> {
+    match args.next() {
+        Some(value) => Ok(value),
+        None => Err(format!("Missing value for {}", option)),
+    }
+}
+
+fn parse_points(value: &str) -> Result<Vec<Decimal>, String> {
+    value
+        .split(',')
+        .map(|points| {
+            Decimal::from_str(points.trim()).map_err(|_| format!("\"{}\" is not a valid number of points", points.trim()))
+        })
+        .collect()
+}
+
+// Override a single setting by round tripping the settings through TOML, so every key in
+// settings.toml can be set without having to be listed here.
+fn override_setting(settings: Settings, key: &str, value: &str) -> Result<Settings, String> {
+    let mut table = match toml::Value::try_from(&settings) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => return Err(String::from("Failed to serialize settings")),
+    };
+
+    // Optional settings are left out of the serialized table while they are unset.
+    if !table.contains_key(key) && key != "scoring_system_file_name" && key != "club_directory_file_name" && key != "roster_file_name" {
+        return Err(format!("Unknown setting \"{}\"", key));
+    }
+
+    // Anything that is not valid TOML, such as an unquoted path, is taken as a string.
+    let value = match format!("value = {}", value).parse::<toml::Table>() {
+        Ok(mut parsed) => parsed.remove("value").unwrap_or_else(|| toml::Value::String(value.to_string())),
+        Err(_) => toml::Value::String(value.to_string()),
+    };
+
+    table.insert(key.to_string(), value);
+
+    let mut overridden = toml::Value::Table(table)
+        .try_into::<Settings>()
+        .map_err(|err| format!("Invalid value for setting \"{}\": {}", key, err))?;
+
+    // The club aliases are kept in their own file and are not part of the round trip.
+    overridden.club_aliases = settings.club_aliases;
+
+    Ok(overridden)
+}
+
+fn open_competition(options: &CliOptions) -> Result<Competition, String> {
+    let mut competition = Competition::open(&options.competition, &options.settings)?;
+    eprintln!("{}", competition.status());
+    print_diagnostics(competition.diagnostics());
+
+    // Selection only narrows the events that are checked when the competition is opened.
+    for event in competition.events_mut() {
+        let selected = event.active() && is_event_selected(event, options);
+        event.set_active(selected);
+    }
+
+    Ok(competition)
+}
+
+fn is_event_selected(event: &Event, options: &CliOptions) -> bool {
+    let scoring_system_included = match event.scoring_system() {
+        ScoringSystem::IJS => options.settings.include_ijs,
+        ScoringSystem::SixO => options.settings.include_60,
+    };
+
+    let included = options.include_patterns.is_empty()
+        || options.include_patterns.iter().any(|pattern| matches_pattern(pattern, event.event_name()));
+    let excluded = options.exclude_patterns.iter().any(|pattern| matches_pattern(pattern, event.event_name()));
+
+    scoring_system_included && included && !excluded
+}
+
+fn list_events(events: &[Event]) {
+    for event in events {
+        println!(
+            "[{}] {:<4} {}",
+            if event.active() { "x" } else { " " },
+            event.scoring_system().get_name(),
+            event.event_name(),
+        );
+    }
+}
+
+fn tabulate(competition: &Competition, settings: &Settings) -> Option<Standings> {
+    if !competition.events().iter().any(|event| event.active()) {
+        eprintln!("No events selected");
+        return None;
+    }
+
+    let standings = competition.tabulate(&Rules::from(settings));
+    eprintln!("{}", standings.status());
+    print_diagnostics(standings.diagnostics());
+
+    if !standings.is_ok() {
+        return None;
+    }
+
+    print_merge_suggestions(standings.club_points(), settings);
+
+    Some(standings)
+}
+
+fn print_diagnostics(diagnostics: &[ParseError]) {
+    for diagnostic in diagnostics {
+        eprintln!("warning: {}", diagnostic);
+    }
+}
+
+// Point out clubs that look like they are listed under two names. They are merged by adding an
+// alias, either in the GUI or in club_aliases.toml.
+fn print_merge_suggestions(club_points: &[ClubPoints], settings: &Settings) {
+    for suggestion in merge_suggestions(club_points, settings) {
+        eprintln!(
+            "note: \"{}\" may be the same club as \"{}\" ({}% match). Add a club alias to merge them.",
+            suggestion.club(),
+            suggestion.target(),
+            suggestion.confidence(),
+        );
+    }
+}
+
+fn print_standings(standings: &Standings, settings: &Settings) {
+    if settings.standings_mode.includes_points() {
+        print_club_points(standings.club_points(), settings, "Club");
+
+        let team_points = standings.team_points();
+        if !team_points.is_empty() {
+            println!();
+            print_club_points(team_points, settings, "Team");
+
+            let unassigned = standings.unassigned_clubs();
+            if !unassigned.is_empty() {
+                eprintln!("note: {} club(s) are on no team: {}", unassigned.len(), unassigned.join(", "));
+            }
+        }
+    }
+
+    if settings.standings_mode.includes_medals() {
+        if settings.standings_mode.includes_points() {
+            println!();
+        }
+        print_medal_table(standings.medal_table(), settings);
+    }
+}
+
+fn print_medal_table(medal_counts: &[MedalCount], settings: &Settings) {
+    let club_width = medal_counts.iter().map(|medal_count| medal_count.club().chars().count()).max().unwrap_or(0).max(4);
+    let place_names = PLACE_NAMES.iter().take(medal_table_places(settings)).map(|place_name| format!("  {:>6}", place_name)).collect::<String>();
+
+    println!("{:>4}  {:<club_width$}{}  {:>6}", "#", "Club", place_names, "Total");
+    for (rank, medal_count) in medal_ranks(medal_counts).iter().zip(medal_counts) {
+        let medals = medal_count.medals().iter().map(|medals| format!("  {:>6}", medals)).collect::<String>();
+        println!("{:>4}  {:<club_width$}{}  {:>6}", rank, medal_count.club(), medals, medal_count.total());
+    }
+}
+
+// Print the points standings of the clubs, or of the teams they are grouped into, as named by the header.
+fn print_club_points(club_points: &[ClubPoints], settings: &Settings, name_header: &str) {
+    let club_width = club_points.iter().map(|club_points| club_points.club().chars().count()).max().unwrap_or(0).max(4);
+
+    let participation = awards_participation_points(settings);
+
+    let participation_header = if participation { format!("  {:>13}", "Participation") } else { String::new() };
+    let disciplines = subtotal_disciplines(club_points, settings);
+    let discipline_headers = disciplines.iter().map(|discipline| format!("  {:>12}", discipline.get_name())).collect::<String>();
+
+    println!("{:>4}  {:<club_width$}  {:>8}  {:>8}{}  {:>8}{}", "#", name_header, "IJS", "6.0", participation_header, "Total", discipline_headers);
+    let ranks = shared_ranks(club_points, &settings.standings_tie_breakers);
+    for (i, club_points) in club_points.iter().enumerate() {
+        let participation_points = if participation {
+            format!("  {:>13}", display_points(club_points.points_participation().unwrap_or_default(), settings))
+        } else {
+            String::new()
+        };
+
+        let subtotals = disciplines
+            .iter()
+            .map(|discipline| format!("  {:>12}", display_points(club_points.points_discipline(*discipline), settings)))
+            .collect::<String>();
+
+        println!(
+            "{:>4}  {:<club_width$}  {:>8}  {:>8}{}  {:>8}{}",
+            ranks[i],
+            club_points.club(),
+            display_points(club_points.points_ijs().unwrap_or_default(), settings),
+            display_points(club_points.points_60().unwrap_or_default(), settings),
+            participation_points,
+            display_points(club_points.calc_total(), settings),
+            subtotals,
+        );
+    }
+}
@@ -0,0 +1,108 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::competition::rules::Rules;
+use crate::competition::standings::Standings;
+use crate::io::file_utils;
+use crate::io::html::event::Event;
+use crate::io::html::parse_error::ParseError;
+use crate::io::html::parser::State;
+use crate::io::html::{event, parser};
+use crate::settings::settings::Settings;
+
+// A competition whose results directory has been found and whose events have been read.
+#[derive(Debug, Clone)]
+pub struct Competition {
+    name: String,
+    path: String,
+    events: Vec<Event>,
+    diagnostics: Vec<ParseError>,
+    status: String,
+}
+
+impl Competition {
+    // Open a competition by name or absolute path, depending on the settings, and read its events.
+    pub fn open(competition: &str, settings: &Settings) -> Result<Self, String> {
+        let path = match file_utils::get_directory(competition.to_string(), settings) {
+            Ok(path) => path,
+            Err(_) => return Err(String::from("No competition found.")),
+        };
+
+        let (events, diagnostics, status, state) = event::retrieve_events(path.clone());
+
+        match state {
+            State::Ok => {
+                let (events, note) = event::apply_ijs_results_counting(events, settings.ijs_results_counting);
+                let status = match note {
+                    Some(note) => format!("{} {}", status, note),
+                    None => status,
+                };
+
+                Ok(Self::new(competition.to_string(), path, events, diagnostics, status))
+            }
+            State::Error => Err(status),
+        }
+    }
+
+    pub fn new(name: String, path: String, events: Vec<Event>, diagnostics: Vec<ParseError>, status: String) -> Self {
+        Self {
+            name,
+            path,
+            events,
+            diagnostics,
+            status,
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+
+    pub fn events(&self) -> &Vec<Event> {
+        &self.events
+    }
+
+    pub fn events_mut(&mut self) -> &mut Vec<Event> {
+        &mut self.events
+    }
+
+    // Problems found while reading the events. The affected files are not in the event list.
+    pub fn diagnostics(&self) -> &Vec<ParseError> {
+        &self.diagnostics
+    }
+
+    pub fn status(&self) -> &String {
+        &self.status
+    }
+
+    // Tabulate the active events according to the given rules.
+    pub fn tabulate(&self, rules: &Rules) -> Standings {
+        let settings = rules.to_settings();
+        let (club_points, team_points, result_sets, diagnostics, status, state) = parser::parse_results(self.events.clone(), &settings);
+
+        Standings::new(club_points, team_points, result_sets, diagnostics, status, matches!(state, State::Ok), &settings)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod competition;
+pub mod rules;
+pub mod standings;
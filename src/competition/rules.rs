@@ -0,0 +1,239 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use rust_decimal::Decimal;
+use crate::io::html::competitor_status::CompetitorStatus;
+use crate::io::html::discipline::DisciplineMapping;
+use crate::io::html::medal_table::StandingsMode;
+use crate::io::html::participation::ParticipationBasis;
+use crate::io::html::points_rule::PointsRule;
+use crate::io::html::points_system::{ChartResolution, PointsSystemKind};
+use crate::io::html::results_sorter::TieBreaker;
+use crate::io::html::team_entry::CrossClubCredit;
+use crate::io::html::team_standings::TeamDefinition;
+use crate::io::html::tie_policy::TiePolicy;
+use crate::settings::club_aliases::ClubAliases;
+use crate::settings::settings::Settings;
+
+// The settings that decide how a competition is scored and its clubs are ranked. Everything
+// else in the settings, such as where results are found and which files are written, is left out.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub(crate) points_system: PointsSystemKind,
+    pub(crate) default_points_system: Vec<Decimal>,
+    pub(crate) points_rules: Vec<PointsRule>,
+    pub(crate) points_formula: String,
+    pub(crate) scoring_system_file_name: Option<String>,
+    pub(crate) chart_resolution: ChartResolution,
+    pub(crate) discipline_mappings: Vec<DisciplineMapping>,
+    pub(crate) tie_policy: TiePolicy,
+    pub(crate) standings_tie_breakers: Vec<TieBreaker>,
+    pub(crate) cross_club_credit: CrossClubCredit,
+    pub(crate) statuses_counted_in_field_size: Vec<CompetitorStatus>,
+    pub(crate) participant_quantity_exclusion_point: u64,
+    pub(crate) small_field_points_multiplier: Decimal,
+    pub(crate) max_scoring_entries_per_club: u64,
+    pub(crate) max_counted_results_per_skater: u64,
+    pub(crate) participation_points: Decimal,
+    pub(crate) participation_basis: ParticipationBasis,
+    pub(crate) attempt_automatic_60_club_name_recombination: bool,
+    pub(crate) club_aliases: ClubAliases,
+    pub(crate) roster_file_name: Option<String>,
+    pub(crate) club_directory_file_name: Option<String>,
+    pub(crate) standings_mode: StandingsMode,
+    pub(crate) medal_table_places: u64,
+    pub(crate) team_definitions: Vec<TeamDefinition>,
+    pub(crate) group_teams_by_region: bool,
+}
+
+impl From<&Settings> for Rules {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            points_system: settings.points_system,
+            default_points_system: settings.default_points_system.clone(),
+            points_rules: settings.points_rules.clone(),
+            points_formula: settings.points_formula.clone(),
+            scoring_system_file_name: settings.scoring_system_file_name.clone(),
+            chart_resolution: settings.chart_resolution,
+            discipline_mappings: settings.discipline_mappings.clone(),
+            tie_policy: settings.tie_policy,
+            standings_tie_breakers: settings.standings_tie_breakers.clone(),
+            cross_club_credit: settings.cross_club_credit,
+            statuses_counted_in_field_size: settings.statuses_counted_in_field_size.clone(),
+            participant_quantity_exclusion_point: settings.participant_quantity_exclusion_point,
+            small_field_points_multiplier: settings.small_field_points_multiplier,
+            max_scoring_entries_per_club: settings.max_scoring_entries_per_club,
+            max_counted_results_per_skater: settings.max_counted_results_per_skater,
+            participation_points: settings.participation_points,
+            participation_basis: settings.participation_basis,
+            attempt_automatic_60_club_name_recombination: settings.attempt_automatic_60_club_name_recombination,
+            club_aliases: settings.club_aliases.clone(),
+            roster_file_name: settings.roster_file_name.clone(),
+            club_directory_file_name: settings.club_directory_file_name.clone(),
+            standings_mode: settings.standings_mode,
+            medal_table_places: settings.medal_table_places,
+            team_definitions: settings.team_definitions.clone(),
+            group_teams_by_region: settings.group_teams_by_region,
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::from(&Settings::default())
+    }
+}
+
+impl Rules {
+    // The scoring code reads its rules from settings, so the rules are handed to it in settings
+    // that leave everything else at its default.
+    pub(crate) fn to_settings(&self) -> Settings {
+        Settings {
+            points_system: self.points_system,
+            default_points_system: self.default_points_system.clone(),
+            points_rules: self.points_rules.clone(),
+            points_formula: self.points_formula.clone(),
+            scoring_system_file_name: self.scoring_system_file_name.clone(),
+            chart_resolution: self.chart_resolution,
+            discipline_mappings: self.discipline_mappings.clone(),
+            tie_policy: self.tie_policy,
+            standings_tie_breakers: self.standings_tie_breakers.clone(),
+            cross_club_credit: self.cross_club_credit,
+            statuses_counted_in_field_size: self.statuses_counted_in_field_size.clone(),
+            participant_quantity_exclusion_point: self.participant_quantity_exclusion_point,
+            small_field_points_multiplier: self.small_field_points_multiplier,
+            max_scoring_entries_per_club: self.max_scoring_entries_per_club,
+            max_counted_results_per_skater: self.max_counted_results_per_skater,
+            participation_points: self.participation_points,
+            participation_basis: self.participation_basis,
+            attempt_automatic_60_club_name_recombination: self.attempt_automatic_60_club_name_recombination,
+            club_aliases: self.club_aliases.clone(),
+            roster_file_name: self.roster_file_name.clone(),
+            club_directory_file_name: self.club_directory_file_name.clone(),
+            standings_mode: self.standings_mode,
+            medal_table_places: self.medal_table_places,
+            team_definitions: self.team_definitions.clone(),
+            group_teams_by_region: self.group_teams_by_region,
+            ..Settings::default()
+        }
+    }
+
+    pub fn set_points_system(&mut self, points_system: PointsSystemKind) {
+        self.points_system = points_system;
+    }
+
+    pub fn set_default_points_system(&mut self, default_points_system: Vec<Decimal>) {
+        self.default_points_system = default_points_system;
+    }
+
+    pub fn set_points_rules(&mut self, points_rules: Vec<PointsRule>) {
+        self.points_rules = points_rules;
+    }
+
+    pub fn set_points_formula(&mut self, points_formula: String) {
+        self.points_formula = points_formula;
+    }
+
+    pub fn set_scoring_system_file_name(&mut self, scoring_system_file_name: Option<String>) {
+        self.scoring_system_file_name = scoring_system_file_name;
+    }
+
+    pub fn set_chart_resolution(&mut self, chart_resolution: ChartResolution) {
+        self.chart_resolution = chart_resolution;
+    }
+
+    pub fn set_discipline_mappings(&mut self, discipline_mappings: Vec<DisciplineMapping>) {
+        self.discipline_mappings = discipline_mappings;
+    }
+
+    pub fn set_tie_policy(&mut self, tie_policy: TiePolicy) {
+        self.tie_policy = tie_policy;
+    }
+
+    pub fn set_standings_tie_breakers(&mut self, standings_tie_breakers: Vec<TieBreaker>) {
+        self.standings_tie_breakers = standings_tie_breakers;
+    }
+
+    pub fn set_cross_club_credit(&mut self, cross_club_credit: CrossClubCredit) {
+        self.cross_club_credit = cross_club_credit;
+    }
+
+    pub fn set_statuses_counted_in_field_size(&mut self, statuses_counted_in_field_size: Vec<CompetitorStatus>) {
+        self.statuses_counted_in_field_size = statuses_counted_in_field_size;
+    }
+
+    pub fn set_participant_quantity_exclusion_point(&mut self, participant_quantity_exclusion_point: u64) {
+        self.participant_quantity_exclusion_point = participant_quantity_exclusion_point;
+    }
+
+    pub fn set_small_field_points_multiplier(&mut self, small_field_points_multiplier: Decimal) {
+        self.small_field_points_multiplier = small_field_points_multiplier;
+    }
+
+    pub fn set_max_scoring_entries_per_club(&mut self, max_scoring_entries_per_club: u64) {
+        self.max_scoring_entries_per_club = max_scoring_entries_per_club;
+    }
+
+    pub fn set_max_counted_results_per_skater(&mut self, max_counted_results_per_skater: u64) {
+        self.max_counted_results_per_skater = max_counted_results_per_skater;
+    }
+
+    pub fn set_participation_points(&mut self, participation_points: Decimal) {
+        self.participation_points = participation_points;
+    }
+
+    pub fn set_participation_basis(&mut self, participation_basis: ParticipationBasis) {
+        self.participation_basis = participation_basis;
+    }
+
+    pub fn set_attempt_automatic_60_club_name_recombination(&mut self, attempt_automatic_60_club_name_recombination: bool) {
+        self.attempt_automatic_60_club_name_recombination = attempt_automatic_60_club_name_recombination;
+    }
+
+    pub fn set_club_aliases(&mut self, club_aliases: ClubAliases) {
+        self.club_aliases = club_aliases;
+    }
+
+    pub fn set_roster_file_name(&mut self, roster_file_name: Option<String>) {
+        self.roster_file_name = roster_file_name;
+    }
+
+    pub fn set_club_directory_file_name(&mut self, club_directory_file_name: Option<String>) {
+        self.club_directory_file_name = club_directory_file_name;
+    }
+
+    pub fn set_standings_mode(&mut self, standings_mode: StandingsMode) {
+        self.standings_mode = standings_mode;
+    }
+
+    pub fn set_medal_table_places(&mut self, medal_table_places: u64) {
+        self.medal_table_places = medal_table_places;
+    }
+
+    pub fn set_team_definitions(&mut self, team_definitions: Vec<TeamDefinition>) {
+        self.team_definitions = team_definitions;
+    }
+
+    pub fn set_group_teams_by_region(&mut self, group_teams_by_region: bool) {
+        self.group_teams_by_region = group_teams_by_region;
+    }
+}
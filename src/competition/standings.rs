@@ -0,0 +1,113 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::io::file_utils;
+use crate::io::html::club_points::ClubPoints;
+use crate::io::html::medal_table::{medal_table, MedalCount};
+use crate::io::html::parse_error::ParseError;
+use crate::io::html::result_set::ResultSet;
+use crate::io::html::team_standings::unassigned_clubs;
+use crate::settings::settings::Settings;
+
+// The outcome of tabulating a competition: the sorted club and team totals, the medal table, and
+// the results they came from.
+#[derive(Debug, Clone)]
+pub struct Standings {
+    club_points: Vec<ClubPoints>,
+    team_points: Vec<ClubPoints>,
+    unassigned_clubs: Vec<String>,
+    medal_table: Vec<MedalCount>,
+    result_sets: Vec<ResultSet>,
+    diagnostics: Vec<ParseError>,
+    status: String,
+    // Whether the points could be calculated at all, e.g. false if the scoring spreadsheet is unreadable.
+    ok: bool,
+}
+
+impl Standings {
+    // The medal table and the clubs on no team are worked out from the club totals and results
+    // according to the settings the competition was tabulated with.
+    pub(crate) fn new(club_points: Vec<ClubPoints>, team_points: Vec<ClubPoints>, result_sets: Vec<ResultSet>, diagnostics: Vec<ParseError>, status: String, ok: bool, settings: &Settings) -> Self {
+        let medal_table = if settings.standings_mode.includes_medals() {
+            medal_table(&result_sets, settings)
+        } else {
+            vec![]
+        };
+
+        Self {
+            team_points,
+            unassigned_clubs: unassigned_clubs(&club_points, settings),
+            medal_table,
+            club_points,
+            result_sets,
+            diagnostics,
+            status,
+            ok,
+        }
+    }
+
+    pub fn club_points(&self) -> &Vec<ClubPoints> {
+        &self.club_points
+    }
+
+    // Replace the club totals, e.g. after they were corrected by hand.
+    pub fn set_club_points(&mut self, club_points: Vec<ClubPoints>) {
+        self.club_points = club_points;
+    }
+
+    // Team totals sorted like the club standings. Empty when clubs are not grouped into teams.
+    pub fn team_points(&self) -> &Vec<ClubPoints> {
+        &self.team_points
+    }
+
+    // The clubs in the standings that are on no team, so their points count toward no team total.
+    pub fn unassigned_clubs(&self) -> &Vec<String> {
+        &self.unassigned_clubs
+    }
+
+    // The medals of every club, sorted by medals. Empty when the standings include no medal table.
+    pub fn medal_table(&self) -> &Vec<MedalCount> {
+        &self.medal_table
+    }
+
+    pub fn result_sets(&self) -> &Vec<ResultSet> {
+        &self.result_sets
+    }
+
+    // Events that were skipped because their results could not be read.
+    pub fn diagnostics(&self) -> &Vec<ParseError> {
+        &self.diagnostics
+    }
+
+    pub fn status(&self) -> &String {
+        &self.status
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.ok
+    }
+
+    // Write every output file enabled in the settings.
+    pub fn output_files(&self, settings: &Settings, competition_name: &String) {
+        file_utils::output_files(&self.club_points, &self.team_points, &self.medal_table, &self.result_sets, settings, competition_name);
+    }
+}
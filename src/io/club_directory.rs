@@ -0,0 +1,223 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::io::html::club_matcher::normalize_club_name;
+use crate::io::html::club_points::ClubPoints;
+use crate::io::html::result_set::ResultSet;
+use crate::io::table_file::{cell, header_column, read_rows};
+use crate::settings::settings::Settings;
+
+// Header names recognized for each column of a club directory, compared ignoring case.
+const NAME_HEADERS: [&str; 4] = ["name", "club", "club name", "official name"];
+const ABBREVIATION_HEADERS: [&str; 4] = ["abbreviation", "abbr", "short name", "acronym"];
+const NUMBER_HEADERS: [&str; 5] = ["number", "club number", "club #", "club no", "id"];
+const REGION_HEADERS: [&str; 3] = ["region", "section", "district"];
+
+// A club as listed by the governing body.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DirectoryClub {
+    name: String,
+    abbreviation: Option<String>,
+    number: Option<String>,
+    region: Option<String>,
+}
+
+impl DirectoryClub {
+    pub fn new(name: String, abbreviation: Option<String>, number: Option<String>, region: Option<String>) -> Self {
+        Self {
+            name,
+            abbreviation,
+            number,
+            region,
+        }
+    }
+
+    // The official name of the club.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn abbreviation(&self) -> Option<&String> {
+        self.abbreviation.as_ref()
+    }
+
+    pub fn number(&self) -> Option<&String> {
+        self.number.as_ref()
+    }
+
+    pub fn region(&self) -> Option<&String> {
+        self.region.as_ref()
+    }
+}
+
+// The governing body's list of clubs, read from a CSV file or the first sheet of a spreadsheet.
+// The first row names the columns. Only the club name column is required.
+#[derive(Debug, Clone, Default)]
+pub struct ClubDirectory {
+    clubs: Vec<DirectoryClub>,
+}
+
+impl ClubDirectory {
+    pub fn new(clubs: Vec<DirectoryClub>) -> Self {
+        Self {
+            clubs,
+        }
+    }
+
+    // The directory the settings point to, if any.
+    pub fn from_settings(settings: &Settings) -> Result<Option<Self>, String> {
+        match settings.club_directory_file_name.as_ref().filter(|path| !path.trim().is_empty()) {
+            Some(path) => ClubDirectory::read(path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn read(path: &str) -> Result<Self, String> {
+        let rows = read_rows(path, "club directory")?;
+
+        ClubDirectory::from_rows(rows).map_err(|err| format!("Failed to read the club directory {}: {}", path, err))
+    }
+
+    fn from_rows(rows: Vec<Vec<String>>) -> Result<Self, String> {
+        let mut rows = rows.into_iter();
+        let headers = rows.next().ok_or_else(|| String::from("the file is empty"))?;
+
+        let column = |names: &[&str]| header_column(&headers, names);
+        let name_column = column(&NAME_HEADERS).ok_or_else(|| String::from("no column is headed \"Name\" or \"Club\""))?;
+        let (abbreviation_column, number_column, region_column) = (column(&ABBREVIATION_HEADERS), column(&NUMBER_HEADERS), column(&REGION_HEADERS));
+
+        let clubs = rows
+            .filter_map(|row| {
+                let name = cell(&row, Some(name_column))?;
+                Some(DirectoryClub::new(name, cell(&row, abbreviation_column), cell(&row, number_column), cell(&row, region_column)))
+            })
+            .collect();
+
+        Ok(ClubDirectory::new(clubs))
+    }
+
+    pub fn clubs(&self) -> &Vec<DirectoryClub> {
+        &self.clubs
+    }
+
+    // The listed club a name from the results refers to, by its official name or abbreviation,
+    // ignoring case, punctuation and abbreviated words such as FSC. A name 6.0 results cut short
+    // refers to the one listed club whose name starts the same way.
+    pub fn find(&self, name: &str) -> Option<&DirectoryClub> {
+        let trimmed = name.trim();
+        let normalized = normalize_club_name(trimmed);
+        if normalized.is_empty() {
+            return None;
+        }
+
+        let found = self.clubs
+            .iter()
+            .find(|club| club.name.trim().eq_ignore_ascii_case(trimmed))
+            .or_else(|| self.clubs.iter().find(|club| club.abbreviation.as_ref().is_some_and(|abbreviation| abbreviation.eq_ignore_ascii_case(trimmed))))
+            .or_else(|| self.clubs.iter().find(|club| normalize_club_name(&club.name) == normalized));
+
+        if found.is_some() || !trimmed.ends_with("...") {
+            return found;
+        }
+
+        let mut candidates = self.clubs.iter().filter(|club| normalize_club_name(&club.name).starts_with(&normalized));
+        match (candidates.next(), candidates.next()) {
+            (Some(club), None) => Some(club),
+            _ => None,
+        }
+    }
+}
+
+// Replace every club name in the results that the directory lists with its official name.
+pub fn apply_official_names(result_sets: &mut [ResultSet], directory: &ClubDirectory) {
+    for result_set in result_sets {
+        for member in &mut result_set.members {
+            if let Some(club) = directory.find(&member.club) {
+                member.club = club.name.clone();
+            }
+        }
+
+        let official = result_set.club.as_ref().and_then(|name| directory.find(name)).map(|club| club.name.clone());
+        if official.is_some() {
+            result_set.club = official;
+        }
+    }
+}
+
+// The clubs in the standings that the directory does not list, so they can be checked before the
+// results are published.
+pub fn unknown_clubs(club_points: &[ClubPoints], settings: &Settings) -> Vec<String> {
+    if settings.club_directory_file_name.as_ref().is_none_or(|path| path.trim().is_empty()) {
+        return vec![];
+    }
+
+    club_points
+        .iter()
+        .filter(|club_points| club_points.directory_club().is_none())
+        .map(|club_points| club_points.club().clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClubDirectory;
+
+    fn directory(rows: &[[&str; 2]]) -> ClubDirectory {
+        let rows = [["Name", "Abbreviation"]].iter().chain(rows).map(|row| row.map(String::from).to_vec()).collect();
+
+        ClubDirectory::from_rows(rows).unwrap()
+    }
+
+    fn found(directory: &ClubDirectory, name: &str) -> Option<String> {
+        directory.find(name).map(|club| club.name().clone())
+    }
+
+    #[test]
+    fn exact_name_comes_before_abbreviation() {
+        let directory = directory(&[["Skating Club of Boston", "SCOB"], ["Scob", ""]]);
+
+        assert_eq!(found(&directory, "scob"), Some(String::from("Scob")));
+    }
+
+    #[test]
+    fn abbreviation_comes_before_normalized_name() {
+        let directory = directory(&[["LA Figure Skating Club", ""], ["Los Angeles Figure Skating Club", "LA FSC"]]);
+
+        assert_eq!(found(&directory, "la fsc"), Some(String::from("Los Angeles Figure Skating Club")));
+        assert_eq!(found(&directory, "LA FSC, Inc."), Some(String::from("LA Figure Skating Club")));
+    }
+
+    #[test]
+    fn truncated_name_finds_the_only_club_it_could_be() {
+        let directory = directory(&[["Skating Club of Boston", ""], ["Skating Club of Boulder", ""]]);
+
+        assert_eq!(found(&directory, "Skating Club of Bos..."), Some(String::from("Skating Club of Boston")));
+        assert_eq!(found(&directory, "Skating Club of Bo..."), None);
+        assert_eq!(found(&directory, "Skating Club of Bos"), None);
+    }
+
+    #[test]
+    fn missing_name_column_is_an_error() {
+        assert!(ClubDirectory::from_rows(vec![vec![String::from("Region")]]).is_err());
+        assert!(ClubDirectory::from_rows(vec![]).is_err());
+    }
+}
@@ -1,10 +1,18 @@
 use calamine::{Data, Range, DataType};
 use std::collections::HashMap;
+use std::str::FromStr;
+use rust_decimal::Decimal;
 use calamine::{open_workbook, Reader, Xlsx};
 
 pub struct ScoringSystemReadError;
 
-pub fn read_scoring_system_spreadsheet(path: Option<String>) -> Result<HashMap<u64, Vec<f64>>, String> {
+pub fn read_scoring_system_spreadsheet(path: Option<String>) -> Result<HashMap<u64, Vec<Decimal>>, String> {
+    read_scoring_system_sheet(path, "Points Chart")
+}
+
+// Read a points chart from any sheet of the scoring system spreadsheet, with the number of
+// participants as the header of each column.
+pub fn read_scoring_system_sheet(path: Option<String>, sheet: &str) -> Result<HashMap<u64, Vec<Decimal>>, String> {
     let path = match path {
         Some(path) => path,
         None => return Err("No scoring system spreadsheet path given".to_string()),
@@ -15,12 +23,12 @@ pub fn read_scoring_system_spreadsheet(path: Option<String>) -> Result<HashMap<u
         Err(_) => return Err("Failed to read scoring system spreadsheet. Is it in the right format?".to_string()),
     };
 
-    let range: Range<Data> = match workbook.worksheet_range("Points Chart") {
+    let range: Range<Data> = match workbook.worksheet_range(sheet) {
         Ok(range) => range,
-        Err(_) => return Err("Failed to read page \"Points Chart.\" Does it exist?".to_string()),
+        Err(_) => return Err(format!("Failed to read page \"{}.\" Does it exist?", sheet)),
     };
 
-    let mut columns: HashMap<u64, Vec<f64>> = HashMap::new();
+    let mut columns: HashMap<u64, Vec<Decimal>> = HashMap::new();
 
     let mut header_row = true;
     let mut headers: Vec<u64> = Vec::new();
@@ -41,7 +49,8 @@ pub fn read_scoring_system_spreadsheet(path: Option<String>) -> Result<HashMap<u
             for (i, cell) in row.iter().enumerate() {
                 if let Some(header) = headers.get(i) {
                     if let Some(column_vec) = columns.get_mut(header) {
-                        column_vec.push(match cell.as_f64() {
+                        // Go through the shortest text form of the number so 0.1 is read as exactly 0.1.
+                        column_vec.push(match cell.as_f64().and_then(|cell_value| Decimal::from_str(&cell_value.to_string()).ok()) {
                             Some(cell_value) => cell_value,
                             None => if cell.is_empty() {
                                 continue
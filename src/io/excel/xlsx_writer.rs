@@ -20,21 +20,118 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use std::path::Path;
+use rust_decimal::prelude::ToPrimitive;
 use rust_xlsxwriter;
-use rust_xlsxwriter::{ColNum, Format, FormatAlign, Formula, Workbook};
-use crate::io::excel::scoring_system_reader::read_scoring_system_spreadsheet;
+use rust_xlsxwriter::{ColNum, Format, FormatAlign, Workbook};
 use crate::io::file_utils;
-use crate::io::html::club_points::ClubPoints;
+use crate::io::html::best_results::{discarded_note, discarded_results};
+use crate::io::html::club_points::{small_field_note, subtotal_disciplines, uncounted_clubs, uncounted_note, ClubPoints};
+use crate::io::html::medal_table::{medal_ranks, medal_table_places, MedalCount, PLACE_NAMES};
+use crate::io::html::participation::awards_participation_points;
+use crate::io::html::points_system::{self, result_points};
 use crate::io::html::result_set::ResultSet;
+use crate::io::html::results_sorter::shared_ranks;
+use crate::io::html::tie_policy::count_ties;
+use crate::io::rounding::display_points;
 use crate::settings::settings::Settings;
 
-pub fn create_xlsx(club_points: &Vec<ClubPoints>, settings: Settings) {
+const PARTICIPATION_COLUMN: usize = 4;
+const MEDAL_TABLE_SHEET_NAME: &str = "Medal Table";
+const TEAM_STANDINGS_SHEET_NAME: &str = "Team Standings";
+
+pub fn create_xlsx(club_points: &Vec<ClubPoints>, team_points: &[ClubPoints], medal_counts: &[MedalCount], settings: Settings) {
     let mut workbook = Workbook::new();
 
+    if settings.standings_mode.includes_points() {
+        write_points_sheet(&mut workbook, club_points, &settings, false);
+
+        if !team_points.is_empty() {
+            write_points_sheet(&mut workbook, team_points, &settings, true);
+        }
+    }
+
+    if settings.standings_mode.includes_medals() {
+        write_medal_table_sheet(&mut workbook, medal_counts, &settings);
+    }
+
+    file_utils::check_and_create_dir(&settings.output_directory);
+
+    let path = settings.xlsx_path();
+
+    for i in 0..i32::MAX {
+        let modified_path = if i != 0 {
+            path.clone().replace(".xlsx", format!("({}).xlsx", i).as_str())
+        } else {
+            path.clone()
+        };
+
+        if Path::new(modified_path.as_str()).exists() {
+            continue;
+        }
+
+        match workbook.save(&modified_path) {
+            Ok(_) => {
+                break;
+            }
+            Err(_) => {
+                continue;
+            }
+        }
+    }
+}
+
+// The points standings, of the clubs or of the teams they are grouped into. The team standings
+// have a sheet of their own, laid out like the club standings with the team in place of the club.
+fn write_points_sheet(workbook: &mut Workbook, club_points: &[ClubPoints], settings: &Settings, teams: bool) {
     let worksheet = workbook.add_worksheet();
+    if teams {
+        if let Err(err) = worksheet.set_name(TEAM_STANDINGS_SHEET_NAME) {
+            eprintln!("Failed to name the team standings sheet: {}", err);
+        }
+    }
 
     worksheet.set_print_gridlines(true);
-    for (column, width) in settings.xlsx_column_widths.iter().enumerate() {
+
+    // Participation points get a column of their own before the total, as wide as the IJS column.
+    let participation = awards_participation_points(settings);
+    let mut column_widths = settings.xlsx_column_widths.clone();
+    let mut header_cell_values = settings.xlsx_header_cell_values.clone();
+    if teams {
+        if let Some(header) = header_cell_values.get_mut(1) {
+            *header = settings.xlsx_team_header_cell_value.clone();
+        }
+    }
+    if participation {
+        column_widths.insert(PARTICIPATION_COLUMN.min(column_widths.len()), column_widths.get(2).copied().unwrap_or(-1));
+        header_cell_values.insert(PARTICIPATION_COLUMN.min(header_cell_values.len()), settings.xlsx_participation_header_cell_value.clone());
+    }
+    let total_column = if participation { PARTICIPATION_COLUMN + 1 } else { PARTICIPATION_COLUMN };
+
+    // Discipline subtotals come after the total, so the total only sums the columns before it.
+    let disciplines = subtotal_disciplines(club_points, settings);
+    if !disciplines.is_empty() {
+        column_widths.resize(total_column + 1, -1);
+        header_cell_values.resize(total_column + 1, String::new());
+    }
+    for discipline in &disciplines {
+        column_widths.push(column_widths.get(2).copied().unwrap_or(-1));
+        header_cell_values.push(discipline.get_name());
+    }
+
+    // The club number and region from the club directory come last.
+    let directory = club_points.iter().any(|club_points| club_points.directory_club().is_some());
+    let directory_column = total_column + 1 + disciplines.len();
+    if directory {
+        column_widths.resize(directory_column, -1);
+        header_cell_values.resize(directory_column, String::new());
+        let default_directory_header_cell_values = Settings::default().xlsx_directory_header_cell_values;
+        for (i, default) in default_directory_header_cell_values.iter().enumerate() {
+            column_widths.push(column_widths.get(4).copied().unwrap_or(-1));
+            header_cell_values.push(settings.xlsx_directory_header_cell_values.get(i).unwrap_or(default).clone());
+        }
+    }
+
+    for (column, width) in column_widths.iter().enumerate() {
         if *width > 0 { // Use negative value to not set the width.
             worksheet
                 .set_column_width(column as ColNum, *width)
@@ -47,52 +144,88 @@ pub fn create_xlsx(club_points: &Vec<ClubPoints>, settings: Settings) {
 
     let text_format = Format::new().set_font_size(settings.xlsx_font_size).set_align(FormatAlign::Center);
 
-    for (column, value) in settings.xlsx_header_cell_values.iter().enumerate() {
+    for (column, value) in header_cell_values.iter().enumerate() {
         worksheet
             .write_with_format(0, column as ColNum, value.as_str(), &text_format)
             .expect(format!("Failed to write \"{}\" to worksheet at (0, {})", value, column)
                 .as_str());
     }
 
+    let ranks = shared_ranks(club_points, &settings.standings_tie_breakers);
+
     for (i, result) in club_points.iter().enumerate() {
-        worksheet.write_with_format(i as u32 + 1, 0, i as u32 + 1, &text_format).expect("Failed to write the rank");
+        worksheet.write_with_format(i as u32 + 1, 0, ranks[i], &text_format).expect("Failed to write the rank");
         worksheet.write_with_format(i as u32 + 1, 1, result.club(), &text_format).expect(format!("Failed to write club name for {}", result.club()).as_str());
-        worksheet.write_with_format(i as u32 + 1, 2, result.points_ijs(), &text_format).expect(format!("Failed to write IJS score for {}", result.club()).as_str());
-        worksheet.write_with_format(i as u32 + 1, 3, result.points_60(), &text_format).expect(format!("Failed to write 6.0 score for {}", result.club()).as_str());
-        worksheet.write_with_format(i as u32 + 1, 4, Formula::new(format!("=SUM(C{}:D{})", i as u32 + 2, i as u32 + 2).as_str()), &text_format).expect(format!("Failed to write total for {}", result.club()).as_str());
+        worksheet.write_with_format(i as u32 + 1, 2, result.points_ijs().and_then(|points| display_points(points, settings).to_f64()), &text_format).expect(format!("Failed to write IJS score for {}", result.club()).as_str());
+        worksheet.write_with_format(i as u32 + 1, 3, result.points_60().and_then(|points| display_points(points, settings).to_f64()), &text_format).expect(format!("Failed to write 6.0 score for {}", result.club()).as_str());
+        if participation {
+            worksheet.write_with_format(i as u32 + 1, PARTICIPATION_COLUMN as ColNum, result.points_participation().and_then(|points| display_points(points, settings).to_f64()), &text_format).expect("Failed to write participation points");
+        }
+        for (j, discipline) in disciplines.iter().enumerate() {
+            worksheet.write_with_format(i as u32 + 1, (total_column + 1 + j) as ColNum, display_points(result.points_discipline(*discipline), settings).to_f64(), &text_format).expect("Failed to write discipline subtotal");
+        }
+        if let Some(directory_club) = result.directory_club() {
+            worksheet.write_with_format(i as u32 + 1, directory_column as ColNum, directory_club.number().map_or("", |number| number.as_str()), &text_format).expect("Failed to write the club number");
+            worksheet.write_with_format(i as u32 + 1, directory_column as ColNum + 1, directory_club.region().map_or("", |region| region.as_str()), &text_format).expect("Failed to write the region");
+        }
+        // The exact total rounded once, rather than a sum of the rounded columns, so it matches the HTML and command line.
+        worksheet.write_with_format(i as u32 + 1, total_column as ColNum, display_points(result.calc_total(), settings).to_f64(), &text_format).expect(format!("Failed to write total for {}", result.club()).as_str());
     }
+}
 
-    file_utils::check_and_create_dir(&settings.output_directory);
+// The medal table on a sheet of its own, with a column for each counted place.
+fn write_medal_table_sheet(workbook: &mut Workbook, medal_counts: &[MedalCount], settings: &Settings) {
+    let worksheet = workbook.add_worksheet();
+    if let Err(err) = worksheet.set_name(MEDAL_TABLE_SHEET_NAME) {
+        eprintln!("Failed to name the medal table sheet: {}", err);
+    }
 
-    let path = settings.xlsx_path();
+    worksheet.set_print_gridlines(true);
 
-    for i in 0..i32::MAX {
-        let modified_path = if i != 0 {
-            path.clone().replace(".xlsx", format!("({}).xlsx", i).as_str())
-        } else {
-            path.clone()
-        };
+    let places = medal_table_places(settings);
 
-        if Path::new(modified_path.as_str()).exists() {
-            continue;
+    // The rank and club columns are as wide as on the points sheet, the rest as wide as its IJS column.
+    let points_width = settings.xlsx_column_widths.get(2).copied().unwrap_or(-1);
+    for column in 0..places + 3 {
+        let width = settings.xlsx_column_widths.get(column).copied().filter(|_| column < 2).unwrap_or(points_width);
+        if width > 0 {
+            if let Err(err) = worksheet.set_column_width(column as ColNum, width) {
+                eprintln!("Could not set column {} to specified width of {}: {}", column, width, err);
+            }
         }
+    }
 
-        match workbook.save(&modified_path) {
-            Ok(_) => {
-                break;
-            }
-            Err(_) => {
-                continue;
-            }
+    let text_format = Format::new().set_font_size(settings.xlsx_font_size).set_align(FormatAlign::Center);
+
+    let header_cell_values = [settings.xlsx_header_cell_values.first().map_or("Rank", |value| value.as_str()), settings.xlsx_header_cell_values.get(1).map_or("Club", |value| value.as_str())]
+        .into_iter()
+        .chain(PLACE_NAMES.iter().take(places).copied())
+        .chain(std::iter::once("Total"));
+
+    for (column, value) in header_cell_values.enumerate() {
+        worksheet.write_with_format(0, column as ColNum, value, &text_format).expect("Failed to write the medal table header");
+    }
+
+    let ranks = medal_ranks(medal_counts);
+
+    for (i, medal_count) in medal_counts.iter().enumerate() {
+        let row = i as u32 + 1;
+        worksheet.write_with_format(row, 0, ranks[i], &text_format).expect("Failed to write the rank");
+        worksheet.write_with_format(row, 1, medal_count.club(), &text_format).expect("Failed to write the club name");
+        for (place, medals) in medal_count.medals().iter().enumerate() {
+            worksheet.write_with_format(row, place as ColNum + 2, *medals, &text_format).expect("Failed to write the medal count");
         }
+        worksheet.write_with_format(row, places as ColNum + 2, medal_count.total(), &text_format).expect("Failed to write the medal total");
     }
 }
 
 pub fn create_xlsx_info_dump(raw_results: &Vec<ResultSet>, settings: Settings) {
-    let spreadsheet_scoring_system = if settings.use_scoring_system_spreadsheet {
-        Some(read_scoring_system_spreadsheet(settings.scoring_system_file_name.clone()).unwrap())
-    } else {
-        None
+    let points_system = match points_system::from_settings(&settings) {
+        Ok(points_system) => points_system,
+        Err(err) => {
+            eprintln!("Failed to write the info dump: {}", err);
+            return
+        }
     };
 
     let mut workbook = Workbook::new();
@@ -102,63 +235,55 @@ pub fn create_xlsx_info_dump(raw_results: &Vec<ResultSet>, settings: Settings) {
     worksheet.set_print_gridlines(true);
     let text_format = Format::new();
 
-    for (column, value) in settings.xlsx_info_dump_header_cell_values.iter().enumerate() {
+    // Older settings files may not name every column, so the default headers fill in the rest.
+    let default_header_cell_values = Settings::default().xlsx_info_dump_header_cell_values;
+    let header_cell_values = settings.xlsx_info_dump_header_cell_values
+        .iter()
+        .chain(default_header_cell_values.iter().skip(settings.xlsx_info_dump_header_cell_values.len()));
+
+    for (column, value) in header_cell_values.enumerate() {
         worksheet
             .write_with_format(0, column as ColNum, value.as_str(), &text_format)
             .expect(format!("Failed to write \"{}\" to worksheet at (0, {})", value, column)
                 .as_str());
     }
 
-    for (i, result) in raw_results.iter().enumerate() {
-        let points = match &spreadsheet_scoring_system {
-            Some(scoring_system) => {
-                let participants = match result.participants {
-                    Some(participants) => participants,
-                    None => {
-                        eprintln!("Failed to get number of participants at event {}",
-                                  result.event.clone().unwrap_or(String::from("Unknown")));
-                        continue
-                    },
-                };
-
-                let scoring_system_for_n_participants = match scoring_system.get(&participants) {
-                    Some(scoring_system) => scoring_system,
-                    None => {
-                        eprintln!("Failed to get scoring system column at event {}",
-                                  result.event.clone().unwrap_or(String::from("Unknown")));
-                        continue
-                    },
-                };
-
-                let rank = match result.rank.clone() {
-                    Some(rank) => rank - 1,
-                    None => {
-                        eprintln!("Failed to get rank for at event {}, skater {}",
-                                  result.event.clone().unwrap_or(String::from("Unknown")),
-                                  result.name.clone().unwrap_or(String::from("Unknown")));
-                        continue
-                    },
-                };
-
-                scoring_system_for_n_participants.get(rank as usize).unwrap_or_else(|| &0.0)
-            }
-            None => {
-                let rank = match result.rank.clone() {
-                    Some(rank) => rank - 1,
-                    None => continue,
-                };
+    let ties = count_ties(raw_results);
+    let discarded = match discarded_results(raw_results, points_system.as_ref(), &settings) {
+        Ok(discarded) => discarded,
+        Err(err) => {
+            eprintln!("Failed to write the info dump: {}", err);
+            return
+        }
+    };
+    let uncounted = uncounted_clubs(raw_results, &discarded, &settings);
 
-                settings.default_points_system.get(rank as usize).unwrap_or_else(|| &0.0)
+    for (i, result) in raw_results.iter().enumerate() {
+        let points = match result_points(points_system.as_ref(), result, &ties, &settings) {
+            Ok(points) => points,
+            Err(err) => {
+                eprintln!("{}", err);
+                continue
             }
         };
 
         worksheet.write_with_format(i as u32 + 1, 0, result.event(), &text_format).expect(format!("Failed to write event name for {}", result.name()).as_str());
         worksheet.write_with_format(i as u32 + 1, 1, result.name(), &text_format).expect(format!("Failed to write skater name for {}", result.name()).as_str());
-        worksheet.write_with_format(i as u32 + 1, 2, result.club(), &text_format).expect(format!("Failed to write club name for {}", result.club()).as_str());
-        worksheet.write_with_format(i as u32 + 1, 3, result.rank(), &text_format).expect(format!("Failed to write rank for {}", result.name()).as_str());
+        worksheet.write_with_format(i as u32 + 1, 2, result.clubs().join(" / "), &text_format).expect(format!("Failed to write club name for {}", result.club()).as_str());
+        if result.is_placed() {
+            worksheet.write_with_format(i as u32 + 1, 3, result.rank(), &text_format).expect(format!("Failed to write rank for {}", result.name()).as_str());
+        }
         worksheet.write_with_format(i as u32 + 1, 4, result.participants(), &text_format).expect(format!("Failed to write number of participants for {}", result.name()).as_str());
-        worksheet.write_with_format(i as u32 + 1, 5, *points, &text_format).expect(format!("Failed to write score for {}", result.name()).as_str());
+        worksheet.write_with_format(i as u32 + 1, 5, display_points(points, &settings).to_f64().unwrap_or_default(), &text_format).expect(format!("Failed to write score for {}", result.name()).as_str());
         worksheet.write_with_format(i as u32 + 1, 6, result.scoring_system.get_name(), &text_format).expect(format!("Failed to write scoring system type for {}", result.name()).as_str());
+        worksheet.write_with_format(i as u32 + 1, 7, result.status().get_name(), &text_format).expect(format!("Failed to write status for {}", result.name()).as_str());
+        let notes = [discarded[i].then(|| discarded_note(&settings)), uncounted_note(&uncounted[i], &settings), small_field_note(result, &settings), points_system.substitution_note(result).filter(|_| result.is_placed())]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>();
+        if !notes.is_empty() {
+            worksheet.write_with_format(i as u32 + 1, 8, notes.join("; "), &text_format).expect(format!("Failed to write notes for {}", result.name()).as_str());
+        }
     }
 
     file_utils::check_and_create_dir(&settings.output_directory);
@@ -1,8 +1,10 @@
 use std::fs;
+use std::io::ErrorKind;
 use std::path::{Path};
 use crate::io::excel::xlsx_writer;
 use crate::io::html::club_points::ClubPoints;
 use crate::io::html::html_writer;
+use crate::io::html::medal_table::MedalCount;
 use crate::io::html::result_set::ResultSet;
 use crate::settings::settings::Settings;
 
@@ -27,9 +29,49 @@ pub fn check_and_create_dir(path: &String) -> bool {
     }
 }
 
-pub fn output_files(club_points: &Vec<ClubPoints>, raw_results: &Vec<ResultSet>, settings: &Settings, competition_name: &String) {
+// Resolve the results directory of a competition from either its name or its absolute path.
+pub fn get_directory(input: String, settings: &Settings) -> Result<String, ErrorKind> {
+    let result;
+    if settings.use_event_name_for_results_path {
+        let isu_calc_base_directory = if !settings.isu_calc_base_directory.ends_with("/") && !settings.isu_calc_base_directory.ends_with("\\") {
+            format!("{}{}", settings.isu_calc_base_directory, "/")
+        } else {
+            settings.isu_calc_base_directory.clone()
+        };
+
+        let html_relative_directory = if !settings.html_relative_directory.starts_with("/") && !settings.html_relative_directory.starts_with("\\") {
+            format!("{}{}", "/", settings.html_relative_directory)
+        } else {
+            settings.html_relative_directory.clone()
+        };
+
+        let path_string = format!("{}{}{}", isu_calc_base_directory, input, html_relative_directory).replace("\r", "").replace("\n", "");
+        let path = Path::new(path_string.as_str());
+
+        if path.is_dir() {
+            result = Ok(path_string);
+        } else {
+            result = Err(ErrorKind::NotFound)
+        }
+
+        result
+    } else {
+        let path_string = format!("{}{}", String::from(input).replace("\r", "").replace("\n", ""), settings.html_relative_directory);
+        let path = Path::new(path_string.as_str());
+
+        if path.is_dir() {
+            result = Ok(path_string);
+        } else {
+            result = Err(ErrorKind::NotFound);
+        }
+
+        result
+    }
+}
+
+pub fn output_files(club_points: &Vec<ClubPoints>, team_points: &Vec<ClubPoints>, medal_counts: &[MedalCount], raw_results: &Vec<ResultSet>, settings: &Settings, competition_name: &String) {
     if settings.generate_xlsx {
-        write_xlsx(club_points, settings);
+        write_xlsx(club_points, team_points, medal_counts, settings);
     }
 
     if settings.generate_xlsx_info_dump {
@@ -37,18 +79,18 @@ pub fn output_files(club_points: &Vec<ClubPoints>, raw_results: &Vec<ResultSet>,
     }
 
     if settings.generate_html {
-        write_html(club_points, settings, competition_name);
+        write_html(club_points, team_points, medal_counts, settings, competition_name);
     }
 }
 
-fn write_xlsx(results: &Vec<ClubPoints>, settings: &Settings) {
-    xlsx_writer::create_xlsx(&results, settings.clone());
+fn write_xlsx(results: &Vec<ClubPoints>, team_results: &[ClubPoints], medal_counts: &[MedalCount], settings: &Settings) {
+    xlsx_writer::create_xlsx(results, team_results, medal_counts, settings.clone());
 }
 
 fn write_xlsx_info_dump(raw_results: &Vec<ResultSet>, settings: &Settings) {
-    xlsx_writer::create_xlsx_info_dump(&raw_results, settings.clone());
+    xlsx_writer::create_xlsx_info_dump(raw_results, settings.clone());
 }
 
-fn write_html(results: &Vec<ClubPoints>, settings: &Settings, competition_name: &String) {
-    html_writer::create_html(&results, settings.clone(), competition_name);
+fn write_html(results: &Vec<ClubPoints>, team_results: &Vec<ClubPoints>, medal_counts: &[MedalCount], settings: &Settings, competition_name: &String) {
+    html_writer::create_html(results, team_results, medal_counts, settings.clone(), competition_name);
 }
\ No newline at end of file
@@ -0,0 +1,160 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use crate::io::html::points_system::{result_points, PointsSystem};
+use crate::io::html::result_set::ResultSet;
+use crate::io::html::tie_policy::count_ties;
+use crate::settings::settings::Settings;
+
+// The skaters of an entry with their own clubs. A single skater is the only member of their entry.
+pub fn skaters_of(result_set: &ResultSet) -> Vec<(String, String)> {
+    if result_set.members().is_empty() {
+        return vec![(result_set.name(), result_set.club())];
+    }
+
+    result_set.members().iter().map(|member| (member.name().clone(), member.club().clone())).collect()
+}
+
+// The same skater is taken to be the same name at the same club, ignoring case and spacing,
+// whether they skated alone or as part of an entry.
+pub fn member_identity(name: &str, club: &str) -> String {
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase();
+
+    format!("{}|{}", normalize(name), normalize(club))
+}
+
+// Which results are discarded because every skater of the entry already has their best
+// max_counted_results_per_skater results counted. Each skater's results include the entries they
+// skate in with others, so a pair counts as long as it is among the best results of either
+// skater. Indexed like the results.
+pub fn discarded_results(results_sets: &[ResultSet], points_system: &dyn PointsSystem, settings: &Settings) -> Result<Vec<bool>, String> {
+    if settings.max_counted_results_per_skater == 0 {
+        return Ok(vec![false; results_sets.len()]);
+    }
+
+    let ties = count_ties(results_sets);
+
+    let mut skaters: HashMap<String, Vec<(usize, Decimal)>> = HashMap::new();
+    for (i, results_set) in results_sets.iter().enumerate().filter(|(_, results_set)| results_set.is_placed()) {
+        let points = result_points(points_system, results_set, &ties, settings)?;
+        for (name, club) in skaters_of(results_set) {
+            skaters.entry(member_identity(&name, &club)).or_default().push((i, points));
+        }
+    }
+
+    let mut counted = vec![false; results_sets.len()];
+    for results in skaters.values_mut() {
+        // Keep the results worth the most, the earlier one when two are worth the same.
+        results.sort_by(|(a_index, a_points), (b_index, b_points)| b_points.cmp(a_points).then(a_index.cmp(b_index)));
+
+        for (i, _) in results.iter().take(settings.max_counted_results_per_skater as usize) {
+            counted[*i] = true;
+        }
+    }
+
+    Ok(results_sets.iter().zip(counted).map(|(results_set, counted)| results_set.is_placed() && !counted).collect())
+}
+
+// Explain why a result does not count toward the team totals.
+pub fn discarded_note(settings: &Settings) -> String {
+    format!("Discarded, only the skater's best {} result(s) count", settings.max_counted_results_per_skater)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use crate::io::html::points_system::FlatTable;
+    use crate::io::html::result_set::ResultSet;
+    use crate::io::html::scoring_system::ScoringSystem;
+    use crate::io::html::team_entry::TeamMember;
+    use crate::settings::settings::Settings;
+    use super::discarded_results;
+
+    fn result(event: &str, rank: u64) -> ResultSet {
+        let mut result_set = ResultSet::new(ScoringSystem::IJS);
+        result_set.event = Some(event.to_string());
+        result_set.rank = Some(rank);
+        result_set.participants = Some(4);
+        result_set
+    }
+
+    fn single(event: &str, rank: u64, name: &str) -> ResultSet {
+        let mut result_set = result(event, rank);
+        result_set.name = Some(name.to_string());
+        result_set.club = Some(String::from("Club A"));
+        result_set
+    }
+
+    fn pair(event: &str, rank: u64, first: &str, second: &str) -> ResultSet {
+        let mut result_set = result(event, rank);
+        result_set.members = vec![
+            TeamMember::new(first.to_string(), String::from("Club A")),
+            TeamMember::new(second.to_string(), String::from("Club A")),
+        ];
+        result_set.name = Some(format!("{} / {}", first, second));
+        result_set.club = Some(String::from("Club A"));
+        result_set
+    }
+
+    fn discarded(results_sets: &[ResultSet], best: u64) -> Vec<bool> {
+        let settings = Settings {
+            max_counted_results_per_skater: best,
+            ..Settings::default()
+        };
+        let points_system = FlatTable::new(vec![Decimal::from(4), Decimal::from(3), Decimal::from(2), Decimal::ONE]);
+
+        discarded_results(results_sets, &points_system, &settings).unwrap()
+    }
+
+    #[test]
+    fn only_the_best_results_of_each_skater_count() {
+        let results_sets = [single("Juvenile Girls", 3, "Ann"), single("Novice Ladies", 1, "Ann"), single("Juvenile Girls", 1, "Bea")];
+
+        assert_eq!(discarded(&results_sets, 1), vec![true, false, false]);
+        assert_eq!(discarded(&results_sets, 0), vec![false, false, false]);
+    }
+
+    #[test]
+    fn pair_counts_among_the_results_of_each_of_its_skaters() {
+        let results_sets = [single("Novice Ladies", 1, "Ann"), pair("Novice Pairs", 2, "Ann", "Bob"), single("Novice Men", 1, "Bob")];
+
+        // The pair is not among the best result of either skater.
+        assert_eq!(discarded(&results_sets, 1), vec![false, true, false]);
+        assert_eq!(discarded(&results_sets, 2), vec![false, false, false]);
+    }
+
+    #[test]
+    fn pair_counts_while_it_is_among_the_best_results_of_one_of_its_skaters() {
+        let results_sets = [single("Novice Ladies", 1, "Ann"), pair("Novice Pairs", 2, "Ann", "Bob")];
+
+        assert_eq!(discarded(&results_sets, 1), vec![false, false]);
+    }
+
+    #[test]
+    fn earlier_result_counts_when_results_tie_at_the_cutoff() {
+        let results_sets = [single("Juvenile Girls", 2, "Ann"), single("Novice Ladies", 1, "Ann"), single("Junior Ladies", 2, "Ann")];
+
+        assert_eq!(discarded(&results_sets, 2), vec![false, false, true]);
+    }
+}
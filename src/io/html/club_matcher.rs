@@ -0,0 +1,210 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::cmp::Ordering;
+use crate::io::html::club_points::ClubPoints;
+use crate::settings::settings::Settings;
+
+// Abbreviations common in club names and what they stand for, so that "SC of Boston" is
+// recognized as "Skating Club of Boston".
+const ABBREVIATIONS: [(&str, &str); 8] = [
+    ("fsc", "figure skating club"),
+    ("sc", "skating club"),
+    ("isc", "ice skating club"),
+    ("sa", "skating association"),
+    ("fsa", "figure skating association"),
+    ("st", "saint"),
+    ("mt", "mount"),
+    ("ft", "fort"),
+];
+
+// Words that make no difference to which club a name refers to.
+const IGNORED_WORDS: [&str; 4] = ["the", "of", "inc", "incorporated"];
+
+// A club that appears to be listed under two names, and the name its points should count under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeSuggestion {
+    club: String,
+    target: String,
+    confidence: u64, // How alike the names are, in percent.
+}
+
+impl MergeSuggestion {
+    // The name that would be merged away.
+    pub fn club(&self) -> &String {
+        &self.club
+    }
+
+    // The name the club's points would count under.
+    pub fn target(&self) -> &String {
+        &self.target
+    }
+
+    pub fn confidence(&self) -> u64 {
+        self.confidence
+    }
+}
+
+// Reduce a club name to lowercase words with punctuation removed and abbreviations spelled out.
+pub fn normalize_club_name(name: &str) -> String {
+    let name = name.trim().trim_end_matches("...").to_lowercase().replace('&', " and ");
+    let name = name.chars().map(|c| if c.is_alphanumeric() { c } else { ' ' }).collect::<String>();
+
+    name.split_whitespace()
+        .map(|word| ABBREVIATIONS.iter().find(|(abbreviation, _)| *abbreviation == word).map_or(word, |(_, expansion)| *expansion))
+        .flat_map(|word| word.split_whitespace())
+        .filter(|word| !IGNORED_WORDS.contains(word))
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// How alike two club names are, in percent. Names that are the same once normalized match fully.
+// A name 6.0 results cut short is compared with the same length of the other name.
+pub fn club_name_similarity(a: &str, b: &str) -> u64 {
+    let (a_truncated, b_truncated) = (a.trim().ends_with("..."), b.trim().ends_with("..."));
+    let (a, b) = (normalize_club_name(a), normalize_club_name(b));
+    if a.is_empty() || b.is_empty() {
+        return 0;
+    }
+
+    let (a, b) = match (a_truncated, b_truncated) {
+        (true, false) => (a.clone(), b.chars().take(a.chars().count()).collect()),
+        (false, true) => (a.chars().take(b.chars().count()).collect(), b.clone()),
+        _ => (a, b),
+    };
+
+    let longest = a.chars().count().max(b.chars().count()) as u64;
+    let distance = edit_distance(&a, &b) as u64;
+
+    // Cut short names match at most 95%, as more than one club could share the start of a name.
+    let similarity = (longest - distance.min(longest)) * 100 / longest;
+    if a_truncated != b_truncated {
+        similarity.min(95)
+    } else {
+        similarity
+    }
+}
+
+// The number of single character insertions, deletions and substitutions to turn a into b.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+// Suggest merging clubs whose names are alike, most confident first. Each club is suggested for
+// merging at most once, and never into a club that is itself suggested for merging. Pairs the
+// operator rejected before, and entries shared between clubs such as "Club A / Club B", are left out.
+pub fn merge_suggestions(club_points: &[ClubPoints], settings: &Settings) -> Vec<MergeSuggestion> {
+    let clubs = club_points
+        .iter()
+        .filter(|club_points| !club_points.club().contains(" / ") && !club_points.club().trim().is_empty())
+        .collect::<Vec<&ClubPoints>>();
+
+    let mut candidates: Vec<MergeSuggestion> = vec![];
+    for (i, a) in clubs.iter().enumerate() {
+        for b in &clubs[i + 1..] {
+            if settings.club_aliases.is_rejected(a.club(), b.club()) {
+                continue;
+            }
+
+            let confidence = club_name_similarity(a.club(), b.club());
+            if confidence < settings.club_match_threshold {
+                continue;
+            }
+
+            let (club, target) = if merges_into(a, b) { (a, b) } else { (b, a) };
+            candidates.push(MergeSuggestion { club: club.club().clone(), target: target.club().clone(), confidence });
+        }
+    }
+
+    candidates.sort_by(|a, b| b.confidence.cmp(&a.confidence).then_with(|| a.club.cmp(&b.club)).then_with(|| a.target.cmp(&b.target)));
+
+    let mut suggestions: Vec<MergeSuggestion> = vec![];
+    for candidate in candidates {
+        let merged = suggestions.iter().any(|suggestion| suggestion.club == candidate.club || suggestion.club == candidate.target || suggestion.target == candidate.club);
+        if !merged {
+            suggestions.push(candidate);
+        }
+    }
+
+    suggestions
+}
+
+// Whether club a should be merged into club b rather than the other way around. A name cut short
+// gives way to a full one, then the club with fewer entries to the one with more, then the
+// shorter name to the longer one, which is more likely to be spelled out in full.
+fn merges_into(a: &ClubPoints, b: &ClubPoints) -> bool {
+    let (a_truncated, b_truncated) = (a.club().ends_with("..."), b.club().ends_with("..."));
+    if a_truncated != b_truncated {
+        return a_truncated;
+    }
+
+    match a.entries().cmp(&b.entries()) {
+        Ordering::Less => true,
+        Ordering::Greater => false,
+        Ordering::Equal => a.club().len() < b.club().len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{club_name_similarity, normalize_club_name};
+
+    #[test]
+    fn abbreviations_are_spelled_out() {
+        assert_eq!(normalize_club_name("SC of Boston"), "skating club boston");
+        assert_eq!(normalize_club_name("Los Angeles FSC"), "los angeles figure skating club");
+        assert_eq!(normalize_club_name("St. Moritz ISC"), "saint moritz ice skating club");
+    }
+
+    #[test]
+    fn case_punctuation_and_ignored_words_make_no_difference() {
+        assert_eq!(normalize_club_name("The Skating Club of Boston, Inc."), normalize_club_name("skating club boston"));
+        assert_eq!(normalize_club_name("Ice & Snow SC"), "ice and snow skating club");
+        assert_eq!(club_name_similarity("SC of Boston", "Skating Club of Boston, Inc."), 100);
+    }
+
+    #[test]
+    fn truncated_name_matches_at_most_95_percent() {
+        assert_eq!(normalize_club_name("Skating Club of Bo..."), "skating club bo");
+        assert_eq!(club_name_similarity("Skating Club of Bo...", "Skating Club of Boston"), 95);
+        assert_eq!(club_name_similarity("Skating Club of Boston", "Skating Club of Bo..."), 95);
+        assert!(club_name_similarity("Skating Club of Bo...", "Los Angeles FSC") < 50);
+    }
+
+    #[test]
+    fn empty_name_matches_nothing() {
+        assert_eq!(club_name_similarity("", "Skating Club of Boston"), 0);
+        assert_eq!(club_name_similarity("The Inc.", "Skating Club of Boston"), 0);
+    }
+}
@@ -1,24 +1,40 @@
 use std::collections::HashMap;
 use iced::widget::shader::wgpu::naga::FastHashMap;
+use rust_decimal::Decimal;
+use crate::io::club_directory::DirectoryClub;
 
-use crate::io::excel::scoring_system_reader::read_scoring_system_spreadsheet;
+use crate::io::html::best_results::discarded_results;
+use crate::io::html::discipline::{discipline_of, Discipline};
+use crate::io::html::participation::participation_credits;
+use crate::io::html::points_system::{result_points, PointsSystem};
 use crate::io::html::result_set::ResultSet;
 use crate::io::html::scoring_system::ScoringSystem;
+use crate::io::html::tie_policy::count_ties;
 use crate::settings::settings::Settings;
 
 #[derive(Clone, Debug)]
 pub struct ClubPoints {
     club: String,
-    points_ijs: Option<f64>,
-    points_60: Option<f64>,
+    points_ijs: Option<Decimal>,
+    points_60: Option<Decimal>,
+    points_participation: Option<Decimal>,
+    points_by_discipline: HashMap<Discipline, Decimal>,
+    placements: Vec<u64>, // How many times the club's entries finished in each place, first place first.
+    entries: u64,
+    directory_club: Option<DirectoryClub>, // The club's listing in the club directory, when one is used and lists it.
 }
 
 impl ClubPoints {
     pub fn new(club: String) -> Self {
         Self {
             club,
-            points_ijs: Some(0.0),
-            points_60: Some(0.0),
+            points_ijs: Some(Decimal::ZERO),
+            points_60: Some(Decimal::ZERO),
+            points_participation: Some(Decimal::ZERO),
+            points_by_discipline: HashMap::new(),
+            placements: vec![],
+            entries: 0,
+            directory_club: None,
         }
     }
 
@@ -26,26 +42,71 @@ impl ClubPoints {
         &self.club
     }
 
-    pub fn points_ijs(&self) -> Option<f64> {
+    pub fn points_ijs(&self) -> Option<Decimal> {
         self.points_ijs
     }
 
-    pub fn points_60(&self) -> Option<f64> {
+    pub fn points_60(&self) -> Option<Decimal> {
         self.points_60
     }
 
+    pub fn points_participation(&self) -> Option<Decimal> {
+        self.points_participation
+    }
+
+    // The club's placement points from the events of a discipline.
+    pub fn points_discipline(&self, discipline: Discipline) -> Decimal {
+        self.points_by_discipline.get(&discipline).copied().unwrap_or_default()
+    }
+
+    // How many of the club's counted entries finished in a one based place.
+    pub fn placements(&self, place: u64) -> u64 {
+        match place {
+            0 => 0,
+            place => self.placements.get(place as usize - 1).copied().unwrap_or(0),
+        }
+    }
+
+    // How many entries the club had across the competition, whatever their result.
+    pub fn entries(&self) -> u64 {
+        self.entries
+    }
+
+    pub fn directory_club(&self) -> Option<&DirectoryClub> {
+        self.directory_club.as_ref()
+    }
+
+    pub fn set_directory_club(&mut self, directory_club: Option<DirectoryClub>) {
+        self.directory_club = directory_club;
+    }
+
+    fn add_placement(&mut self, place: u64) {
+        if place == 0 {
+            return;
+        }
+
+        if self.placements.len() < place as usize {
+            self.placements.resize(place as usize, 0);
+        }
+        self.placements[place as usize - 1] += 1;
+    }
+
     pub fn set_club(&mut self, club: String) {
         self.club = club;
     }
 
-    pub fn set_points_ijs(&mut self, points_ijs: f64) {
+    pub fn set_points_ijs(&mut self, points_ijs: Decimal) {
         self.points_ijs.replace(points_ijs);
     }
 
-    pub fn set_points_60(&mut self, points_60: f64) {
+    pub fn set_points_60(&mut self, points_60: Decimal) {
         self.points_60.replace(points_60);
     }
 
+    pub fn set_points_participation(&mut self, points_participation: Decimal) {
+        self.points_participation.replace(points_participation);
+    }
+
     pub fn set_points_ijs_none(&mut self) {
         self.points_ijs = None;
     }
@@ -54,151 +115,270 @@ impl ClubPoints {
         self.points_60 = None;
     }
 
-    pub fn calc_total(&self) -> f64 {
-        self.points_ijs.unwrap_or(0.0) + self.points_60.unwrap_or(0.0)
+    pub fn set_points_participation_none(&mut self) {
+        self.points_participation = None;
+    }
+
+    // Add the points, placements and entries of another club, e.g. one listed under another name.
+    pub fn merge(&mut self, other: &ClubPoints) {
+        if let Some(ref mut points_ijs) = self.points_ijs {
+            *points_ijs += other.points_ijs.unwrap_or_default();
+        }
+
+        if let Some(ref mut points_60) = self.points_60 {
+            *points_60 += other.points_60.unwrap_or_default();
+        }
+
+        if let Some(ref mut points_participation) = self.points_participation {
+            *points_participation += other.points_participation.unwrap_or_default();
+        }
+
+        for (place, count) in other.placements.iter().enumerate() {
+            for _ in 0..*count {
+                self.add_placement(place as u64 + 1);
+            }
+        }
+        self.entries += other.entries;
+
+        for (discipline, points) in &other.points_by_discipline {
+            *self.points_by_discipline.entry(*discipline).or_default() += *points;
+        }
+    }
+
+    pub fn calc_total(&self) -> Decimal {
+        self.points_ijs.unwrap_or_default() + self.points_60.unwrap_or_default() + self.points_participation.unwrap_or_default()
     }
 }
 
-pub fn sum_results(results_sets: &Vec<ResultSet>, settings: Settings) -> FastHashMap<String, ClubPoints> {
-    let spreadsheet_scoring_system = if settings.use_scoring_system_spreadsheet {
-        Some(read_scoring_system_spreadsheet(settings.scoring_system_file_name).unwrap())
-    } else {
-        None
-    };
+// The disciplines any club earned points in, in the usual order, when subtotals are shown.
+pub fn subtotal_disciplines(club_points: &[ClubPoints], settings: &Settings) -> Vec<Discipline> {
+    if !settings.show_discipline_subtotals {
+        return vec![];
+    }
+
+    Discipline::ALL
+        .into_iter()
+        .filter(|discipline| club_points.iter().any(|club_points| !club_points.points_discipline(*discipline).is_zero()))
+        .collect()
+}
 
+// Sum the points of every club. Fails if any placed result cannot be scored, rather than leave
+// its points out of the totals.
+pub fn sum_results(results_sets: &[ResultSet], points_system: &dyn PointsSystem, settings: Settings) -> Result<FastHashMap<String, ClubPoints>, String> {
+    let mut club_points_hashmap = sum_credits(results_sets, points_system, &settings, &|club: &str| Some(club.to_string()))?;
+
+    if settings.attempt_automatic_60_club_name_recombination {
+        auto_club_combiner_hashmap(&mut club_points_hashmap);
+    }
+
+    Ok(club_points_hashmap)
+}
+
+// Sum the points of every result for whoever its clubs are credited to, such as the clubs
+// themselves or their teams. Clubs credited to no one are left out.
+pub fn sum_credits(results_sets: &[ResultSet], points_system: &dyn PointsSystem, settings: &Settings, credited_to: &dyn Fn(&str) -> Option<String>) -> Result<FastHashMap<String, ClubPoints>, String> {
     let mut club_points_hashmap: FastHashMap<String, ClubPoints> = FastHashMap::default();
+    let credited_names = |results_set: &ResultSet| {
+        let mut names: Vec<String> = vec![];
+        for name in results_set.clubs().iter().filter_map(|club| credited_to(club)) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    };
 
-    for results_set in results_sets {
-        let club = results_set.club.clone().unwrap();
-        let club_exists = club_points_hashmap.contains_key(&club);
+    // Clubs whose only entries withdrew or were not placed are left out of the standings.
+    for results_set in results_sets.iter().filter(|results_set| results_set.is_placed()) {
+        for name in credited_names(results_set) {
+            club_points_hashmap.entry(name.clone()).or_insert_with(|| ClubPoints::new(name));
+        }
+    }
 
-        if !club_exists {
-            club_points_hashmap.insert(club.clone(), ClubPoints::new(club));
+    // Clubs whose entries only earned participation points still take part in the standings.
+    let participation = participation_credits(results_sets, settings);
+    for (club, _) in participation.iter().flatten() {
+        if let Some(name) = credited_to(club) {
+            club_points_hashmap.entry(name.clone()).or_insert_with(|| ClubPoints::new(name));
         }
     }
 
     for results_set in results_sets {
-        let club = results_set.club.clone().unwrap();
-        let club = match club_points_hashmap.get_mut(&club) {
-            Some(club) => club,
+        for name in credited_names(results_set) {
+            if let Some(club) = club_points_hashmap.get_mut(&name) {
+                club.entries += 1;
+            }
+        }
+    }
+
+    let ties = count_ties(results_sets);
+    let discarded = discarded_results(results_sets, points_system, settings)?;
+    let uncounted = uncounted_clubs(results_sets, &discarded, settings);
+
+    for (i, results_set) in results_sets.iter().enumerate() {
+        if !results_set.is_placed() || discarded[i] {
+            continue;
+        }
+
+        let points = result_points(points_system, results_set, &ties, settings)?;
+        let discipline = discipline_of(&results_set.event(), &settings.discipline_mappings);
+        let credits = settings.cross_club_credit
+            .credits(results_set, points)
+            .into_iter()
+            .filter(|(club, _)| !uncounted[i].contains(club));
+
+        for (name, points) in combine_credits(credits, points, credited_to) {
+            let club = match club_points_hashmap.get_mut(&name) {
+                Some(club) => club,
+                None => continue,
+            };
+
+            match results_set.scoring_system {
+                ScoringSystem::IJS => { club.points_ijs.replace(club.points_ijs.unwrap_or_default() + points); }
+                ScoringSystem::SixO => { club.points_60.replace(club.points_60.unwrap_or_default() + points); }
+            };
+            *club.points_by_discipline.entry(discipline).or_default() += points;
+            club.add_placement(results_set.rank.unwrap_or(0));
+        }
+    }
+
+    for credits in participation {
+        for (name, points) in combine_credits(credits, settings.participation_points, credited_to) {
+            if let Some(club) = club_points_hashmap.get_mut(&name) {
+                club.points_participation.replace(club.points_participation.unwrap_or_default() + points);
+            }
+        }
+    }
+
+    Ok(club_points_hashmap)
+}
+
+// The credits of one entry or skater, added up for each name they are credited to. A name never
+// receives more than the points themselves, so an entry that credits each of its clubs in full
+// counts once for a team of those clubs.
+fn combine_credits(credits: impl IntoIterator<Item = (String, Decimal)>, points: Decimal, credited_to: &dyn Fn(&str) -> Option<String>) -> Vec<(String, Decimal)> {
+    let mut combined: Vec<(String, Decimal)> = vec![];
+    for (club, club_points) in credits {
+        let name = match credited_to(&club) {
+            Some(name) => name,
             None => continue,
         };
 
-        if spreadsheet_scoring_system.is_some() ||
-            results_set.rank.clone().unwrap() <= settings.default_points_system.len() as u64 {
-
-            let rank = match results_set.rank.clone() {
-                Some(rank) => (rank - 1) as usize,
-                None => {
-                    eprintln!("Failed to get rank for at event {}, skater {}",
-                              results_set.event.clone().unwrap_or(String::from("Unknown")),
-                              results_set.name.clone().unwrap_or(String::from("Unknown")));
-                    continue
-                }
-            };
+        match combined.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, total)) => *total = (*total + club_points).min(points),
+            None => combined.push((name, club_points)),
+        }
+    }
 
-            match &spreadsheet_scoring_system {
-                Some(scoring_system) => {
-                    let participants = match results_set.participants {
-                        Some(participants) => participants,
-                        None => {
-                            eprintln!("Failed to get number of participants at event {}",
-                                      results_set.event.clone().unwrap_or(String::from("Unknown")));
-                            continue
-                        },
-                    };
-
-                    let scoring_system_for_n_participants = match scoring_system.get(&participants) {
-                        Some(scoring_system_for_n_participants) => scoring_system_for_n_participants,
-                        None => {
-                            eprintln!("Failed to get scoring system column at event {}",
-                                      results_set.event.clone().unwrap_or(String::from("Unknown")));
-                            continue
-                        },
-                    };
-
-                    match results_set.scoring_system {
-                        ScoringSystem::IJS => {
-                            club.points_ijs.replace(
-                                club
-                                    .points_ijs()
-                                    .unwrap_or(0.0) + scoring_system_for_n_participants
-                                    .get(rank)
-                                    .unwrap_or(&0.0));
-                        }
-
-                        ScoringSystem::SixO => {
-                            club.points_60.replace(
-                                club
-                                    .points_60()
-                                    .unwrap_or(0.0) + scoring_system_for_n_participants
-                                    .get(rank)
-                                    .unwrap_or(&0.0));
-                        }
-                    };
-                }
-                None => {
-                    match results_set.scoring_system {
-                        ScoringSystem::IJS => { club.points_ijs.replace(club.points_ijs.unwrap_or(0.0) + settings.default_points_system[rank]); }
-                        ScoringSystem::SixO => { club.points_60.replace(club.points_60().unwrap_or(0.0) + settings.default_points_system[rank]); }
-                    };
-                }
+    combined
+}
+
+// The clubs each result does not count for because the club already has its best
+// max_scoring_entries_per_club finishers in the event. Results discarded for the skater take up
+// none of a club's entries. Indexed like the results.
+pub fn uncounted_clubs(results_sets: &[ResultSet], discarded: &[bool], settings: &Settings) -> Vec<Vec<String>> {
+    let mut uncounted = vec![vec![]; results_sets.len()];
+    if settings.max_scoring_entries_per_club == 0 {
+        return uncounted;
+    }
+
+    // Go through each event from the best finisher down, so every club keeps its best entries.
+    let mut placed = (0..results_sets.len()).filter(|i| results_sets[*i].is_placed() && !discarded[*i]).collect::<Vec<usize>>();
+    placed.sort_by_key(|i| results_sets[*i].rank.unwrap_or(u64::MAX));
+
+    let mut counted: HashMap<(String, String), u64> = HashMap::new();
+    for i in placed {
+        for (club, _) in settings.cross_club_credit.credits(&results_sets[i], Decimal::ONE) {
+            let entries = counted.entry((results_sets[i].event(), club.clone())).or_insert(0);
+            if *entries < settings.max_scoring_entries_per_club {
+                *entries += 1;
+            } else {
+                uncounted[i].push(club);
             }
         }
     }
 
-    if settings.attempt_automatic_60_club_name_recombination {
-        auto_club_combiner_hashmap(&mut club_points_hashmap);
+    uncounted
+}
+
+// Explain which clubs a result is not counted for.
+pub fn uncounted_note(uncounted_clubs: &[String], settings: &Settings) -> Option<String> {
+    if uncounted_clubs.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "Not counted for {}, over the limit of {} scoring entries per club in each event",
+        uncounted_clubs.join(" / "),
+        settings.max_scoring_entries_per_club,
+    ))
+}
+
+// Whether the result comes from an event with fewer participants than the minimum field size.
+pub fn is_small_field(results_set: &ResultSet, settings: &Settings) -> bool {
+    settings.participant_quantity_exclusion_point > 0 && results_set.participants() < settings.participant_quantity_exclusion_point
+}
+
+// Explain why a result from an event below the minimum field size earns fewer points.
+pub fn small_field_note(results_set: &ResultSet, settings: &Settings) -> Option<String> {
+    if !is_small_field(results_set, settings) {
+        return None;
     }
 
-    club_points_hashmap
+    let effect = if settings.small_field_points_multiplier.is_zero() {
+        String::from("no points awarded")
+    } else {
+        format!("points multiplied by {}", settings.small_field_points_multiplier)
+    };
+
+    Some(format!(
+        "Field of {} is below the minimum of {}, {}",
+        results_set.participants(),
+        settings.participant_quantity_exclusion_point,
+        effect,
+    ))
 }
 
+// The names of every event below the minimum field size, in the order they appear.
+pub fn small_field_events(results_sets: &[ResultSet], settings: &Settings) -> Vec<String> {
+    let mut events: Vec<String> = vec![];
+    for results_set in results_sets {
+        if is_small_field(results_set, settings) && !events.contains(&results_set.event()) {
+            events.push(results_set.event());
+        }
+    }
+
+    events
+}
+
+// Merge the clubs whose names 6.0 results cut short, e.g. "Skating Club of Bo...", into the club
+// with the full name. A cut short name that could belong to more than one club is left alone,
+// since guessing could credit the wrong club. Those are offered as merge suggestions instead.
 pub fn auto_club_combiner_hashmap(club_points: &mut FastHashMap<String, ClubPoints>) {
-    let truncated_clubs: Vec<(String, ClubPoints)> = club_points
+    let truncated_clubs = truncated_club_merges(&club_points.keys().cloned().collect::<Vec<String>>());
+
+    for (truncated_key, full_key) in truncated_clubs {
+        if let Some(truncated_club) = club_points.remove(&truncated_key) {
+            if let Some(full_club_points) = club_points.get_mut(&full_key) {
+                full_club_points.merge(&truncated_club);
+            }
+        }
+    }
+}
+
+// Pair every cut short club name with the one club whose full name it could be, as
+// (truncated, full). Names that match no club or several clubs are left out.
+pub(crate) fn truncated_club_merges(clubs: &[String]) -> Vec<(String, String)> {
+    clubs
         .iter()
-        .filter(|(key, _)| key.ends_with("..."))
-        .filter_map(|(key, truncated_club_points)| {
-            let truncated_key = key.strip_suffix("...")?;
+        .filter_map(|club| {
+            let prefix = club.strip_suffix("...")?;
+            let mut full_clubs = clubs.iter().filter(|full_club| *full_club != club && full_club.starts_with(prefix));
 
-            if club_points.iter().any(|(full_key, _)| full_key != key && full_key.starts_with(truncated_key)) {
-                Some((truncated_key.to_string(), truncated_club_points.clone()))
-            } else {
-                None
+            match (full_clubs.next(), full_clubs.next()) {
+                (Some(full_club), None) => Some((club.clone(), full_club.clone())),
+                _ => None,
             }
         })
-        .collect();
-
-    truncated_clubs.iter().for_each(|(truncated_key, truncated_club)| {
-        let club_points_binding = club_points.clone();
-        let club_to_combine = club_points_binding
-            .iter()
-            .find(|(key, _)| key.starts_with(truncated_key));
-
-        match club_to_combine {
-            Some((key, _)) => {
-                match club_points.get_mut(key) {
-                    Some(full_club_points) => {
-                        match &mut full_club_points.points_ijs {
-                            Some(ref mut points_ijs) => {
-                                *points_ijs += truncated_club.points_ijs.unwrap_or(0.0);
-                            }
-                            None => {}
-                        }
-
-                        match &mut full_club_points.points_60 {
-                            Some(ref mut points_60) => {
-                                *points_60 += truncated_club.points_60.unwrap_or(0.0);
-                            }
-                            None => {}
-                        }
-
-                        club_points.remove(&format!("{}{}", truncated_key, "..."));
-                    }
-                    None => {}
-                }
-            }
-            None => {}
-        }
-    });
+        .collect()
 }
\ No newline at end of file
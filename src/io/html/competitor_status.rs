@@ -0,0 +1,63 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use serde_derive::{Deserialize, Serialize};
+
+// The outcome of a skater's entry in an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CompetitorStatus {
+    // Finished with a placement.
+    #[default]
+    Placed,
+    Withdrew,
+    DidNotStart,
+    Disqualified,
+    // Listed in the results without a placement or a reason for it.
+    NoRank,
+}
+
+impl CompetitorStatus {
+    // Read the status written in place of a rank, such as "WD" or "DNS". Returns None for text
+    // that is not a known status.
+    pub fn from_rank_text(text: &str) -> Option<Self> {
+        let text = text.replace("&nbsp;", " ");
+        let text = text.trim().trim_end_matches('.').to_uppercase();
+
+        match text.as_str() {
+            "" | "-" => Some(CompetitorStatus::NoRank),
+            "WD" | "W/D" | "WITHDREW" | "WITHDRAWN" => Some(CompetitorStatus::Withdrew),
+            "DNS" | "DID NOT START" => Some(CompetitorStatus::DidNotStart),
+            "DQ" | "DSQ" | "DISQUALIFIED" => Some(CompetitorStatus::Disqualified),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            CompetitorStatus::Placed => String::from("Placed"),
+            CompetitorStatus::Withdrew => String::from("Withdrew"),
+            CompetitorStatus::DidNotStart => String::from("Did Not Start"),
+            CompetitorStatus::Disqualified => String::from("Disqualified"),
+            CompetitorStatus::NoRank => String::from("No Rank"),
+        }
+    }
+}
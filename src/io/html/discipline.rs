@@ -0,0 +1,101 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::event::matches_pattern;
+
+// The kind of skating an event is for, worked out from words in its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Discipline {
+    Singles,
+    Pairs,
+    Dance,
+    Synchronized,
+    Showcase,
+}
+
+impl Discipline {
+    pub const ALL: [Discipline; 5] = [Discipline::Singles, Discipline::Pairs, Discipline::Dance, Discipline::Synchronized, Discipline::Showcase];
+
+    // Events that are not showcase, pairs, dance or synchronized skating are taken to be singles.
+    // Showcase comes first since showcase events may also be for pairs or couples.
+    pub fn of_event(event_name: &str) -> Self {
+        let words = words(event_name);
+        let has_word = |candidates: &[&str]| words.iter().any(|word| candidates.contains(&word.as_str()));
+
+        if has_word(&["showcase", "artistic"]) {
+            Discipline::Showcase
+        } else if has_word(&["pairs", "pair"]) {
+            Discipline::Pairs
+        } else if has_word(&["dance", "dances", "dancing"]) {
+            Discipline::Dance
+        } else if has_word(&["synchronized", "synchro", "synchronised"]) {
+            Discipline::Synchronized
+        } else {
+            Discipline::Singles
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            Discipline::Singles => String::from("Singles"),
+            Discipline::Pairs => String::from("Pairs"),
+            Discipline::Dance => String::from("Dance"),
+            Discipline::Synchronized => String::from("Synchronized"),
+            Discipline::Showcase => String::from("Showcase"),
+        }
+    }
+}
+
+// Assigns the events matching a wildcard pattern to a discipline, for events whose names do not
+// say what they are for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DisciplineMapping {
+    pub event_pattern: String,
+    pub discipline: Discipline,
+}
+
+impl Default for DisciplineMapping {
+    fn default() -> Self {
+        Self {
+            event_pattern: String::new(),
+            discipline: Discipline::Singles,
+        }
+    }
+}
+
+// The discipline of the first mapping matching the event, or the one its name suggests.
+pub fn discipline_of(event_name: &str, mappings: &[DisciplineMapping]) -> Discipline {
+    mappings
+        .iter()
+        .find(|mapping| !mapping.event_pattern.trim().is_empty() && matches_pattern(mapping.event_pattern.trim(), event_name))
+        .map_or_else(|| Discipline::of_event(event_name), |mapping| mapping.discipline)
+}
+
+// Split an event name into lowercase words. Hyphens stay part of a word so that "Preliminary"
+// does not match "Pre-Preliminary".
+pub(crate) fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')' || c == '/')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
@@ -1,38 +1,100 @@
 use std::{fs, thread};
 use std::sync::{Arc, mpsc, RwLock};
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::parse_error::ParseError;
 use crate::io::html::parser::State;
 use crate::io::html::scoring_system::ScoringSystem;
 
+// Whether a results file holds the final standings of an event or the standings of one segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultsKind {
+    Category,
+    Segment,
+}
+
+// Which IJS results files are counted. 6.0 results are always final results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum IjsResultsCounting {
+    // Count each event once, from its final combined standings.
+    FinalResultsOnly,
+    // Count the standings of every segment as an event of its own.
+    #[default]
+    PerSegment,
+}
+
 #[derive(Debug, Clone)]
 pub struct Event {
     pub(crate) event_name: String,
     pub(crate) file_path: String,
     pub(crate) scoring_system: ScoringSystem,
+    pub(crate) results_kind: ResultsKind,
     pub(crate) active: bool,
 }
 
 impl Event {
-    pub fn new(event_name: String, file_path: String, scoring_system: ScoringSystem, active: bool) -> Self {
+    pub fn new(event_name: String, file_path: String, scoring_system: ScoringSystem, results_kind: ResultsKind, active: bool) -> Self {
         Self {
             event_name,
             file_path,
             scoring_system,
+            results_kind,
             active,
         }
     }
+
+    pub fn event_name(&self) -> &String {
+        &self.event_name
+    }
+
+    pub fn file_path(&self) -> &String {
+        &self.file_path
+    }
+
+    pub fn scoring_system(&self) -> &ScoringSystem {
+        &self.scoring_system
+    }
+
+    pub fn results_kind(&self) -> ResultsKind {
+        self.results_kind
+    }
+
+    // The event a segment belongs to, e.g. "Junior Ladies" for "Junior Ladies - Short Program".
+    pub fn category(&self) -> String {
+        match self.results_kind {
+            ResultsKind::Segment => match self.event_name.rsplit_once(" - ") {
+                Some((category, _)) => category.to_string(),
+                None => self.event_name.clone(),
+            },
+            ResultsKind::Category => self.event_name.clone(),
+        }
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
 }
 
-pub fn retrieve_events(path: String) -> (Vec<Event>, String, State) {
+// Find every event in a competition's results directory. Files that cannot be read are left out
+// and reported in the returned diagnostics.
+pub fn retrieve_events(path: String) -> (Vec<Event>, Vec<ParseError>, String, State) {
     let dir = match fs::read_dir(path.clone()) {
         Ok(e) => e,
-        Err(err) => panic!("{} ({})", err, path),
+        Err(err) => {
+            let error = ParseError::unreadable_file(&path, err);
+            return (vec![], vec![error.clone()], error.to_string(), State::Error);
+        }
     };
 
-    let files = dir.map(|f| {
-        String::from(f.unwrap().file_name().to_str().unwrap())
+    let files = dir.filter_map(|f| {
+        f.ok()?.file_name().to_str().map(String::from)
     }).collect::<Vec<String>>();
 
     let events_ijs = Arc::new(RwLock::new(vec![]));
+    let categories_ijs = Arc::new(RwLock::new(vec![]));
     let events_60 = Arc::new(RwLock::new(vec![]));
 
     //Get all files for 6.0 and IJS separately.
@@ -43,17 +105,28 @@ pub fn retrieve_events(path: String) -> (Vec<Event>, String, State) {
             continue;
         }
 
+        // IJS file names are matched ignoring case, since some systems write them in lower case.
+        let upper_case_file = file.to_uppercase();
+
         //Reading from the protocol sheets seems to be the easiest way to do this locally.
         //The protocol sheets seem to be contained in files that start with SEGM
-        if file.starts_with("SEGM") {
+        if upper_case_file.starts_with("SEGM") {
             events_ijs.write().unwrap().push(String::from(path.clone() + "/" + file.as_str()));
             continue;
         }
+
+        //The final standings of each IJS event are in files such as CAT001RS.htm.
+        if upper_case_file.starts_with("CAT") && upper_case_file.ends_with("RS.HTM") {
+            categories_ijs.write().unwrap().push(path.clone() + "/" + file.as_str());
+            continue;
+        }
     }
 
     let files_ijs_clones = events_ijs.clone();
+    let categories_ijs_clones = categories_ijs.clone();
     let files_ijs_thread = thread::spawn(move || {
         files_ijs_clones.write().unwrap().sort();
+        categories_ijs_clones.write().unwrap().sort();
     });
 
     let files_60_clone = events_60.clone();
@@ -65,38 +138,193 @@ pub fn retrieve_events(path: String) -> (Vec<Event>, String, State) {
     files_60_thread.join().unwrap();
 
     let files_ijs_clones = events_ijs.clone();
-    let (events_ijs_sender, events_ijs_receiver) = mpsc::channel::<Vec<Event>>();
+    let categories_ijs_clones = categories_ijs.clone();
+    let (events_ijs_sender, events_ijs_receiver) = mpsc::channel::<(Vec<Event>, Vec<ParseError>)>();
     thread::spawn(move || {
-        let event_names_ijs = crate::io::html::parser::parse_ijs_event_names(&files_ijs_clones.read().unwrap());
-        events_ijs_sender.send(event_names_ijs).unwrap();
+        let (mut event_names_ijs, mut diagnostics_ijs) = crate::io::html::parser::parse_ijs_event_names(&categories_ijs_clones.read().unwrap(), ResultsKind::Category);
+        let (segment_names_ijs, segment_diagnostics_ijs) = crate::io::html::parser::parse_ijs_event_names(&files_ijs_clones.read().unwrap(), ResultsKind::Segment);
+        event_names_ijs.extend(segment_names_ijs);
+        diagnostics_ijs.extend(segment_diagnostics_ijs);
+        events_ijs_sender.send((event_names_ijs, diagnostics_ijs)).unwrap();
     });
 
     let files_60_clones = events_60.clone();
-    let (events_60_sender, events_60_receiver) = mpsc::channel::<Vec<Event>>();
+    let (events_60_sender, events_60_receiver) = mpsc::channel::<(Vec<Event>, Vec<ParseError>)>();
     thread::spawn(move || {
         let event_names_60 = crate::io::html::parser::parse_60_event_names(&files_60_clones.read().unwrap());
         events_60_sender.send(event_names_60).unwrap();
     });
 
-    let mut event_names = events_ijs_receiver.recv().unwrap();
-    event_names.extend(events_60_receiver.recv().unwrap());
+    let (mut event_names, mut diagnostics) = events_ijs_receiver.recv().unwrap();
+    let (event_names_60, diagnostics_60) = events_60_receiver.recv().unwrap();
+    event_names.extend(event_names_60);
+    diagnostics.extend(diagnostics_60);
 
-    let event_names_clone = event_names.clone();
-    let mut event_names_temp = event_names_clone.iter().map(|v| {
-        v.event_name.as_str()
-    }).collect::<Vec<&str>>();
+    event_names = crate::io::html::parser::clean_event_names(event_names);
 
-    human_sort::sort(&mut event_names_temp);
+    // Sort the events themselves so every name stays with its file, and so segments end up
+    // next to the final results of their event.
+    event_names.sort_by(|a, b| human_sort::compare(&a.event_name, &b.event_name));
 
-    for (i, event_name) in event_names_temp.iter().enumerate() {
-        event_names[i].event_name = event_name.to_string();
+    if event_names.len() == 0 {
+        return (event_names, diagnostics, "The specified competition exists, but there are no results at this time.".to_string(), State::Error);
     }
 
-    event_names = crate::io::html::parser::clean_event_names(event_names);
+    (event_names, diagnostics, format!(
+        "Found {} IJS final results, {} IJS segments and {} 6.0 events.",
+        categories_ijs.read().unwrap().len(),
+        events_ijs.read().unwrap().len(),
+        events_60.read().unwrap().len(),
+    ), State::Ok)
+}
 
-    if event_names.len() == 0 {
-        return (event_names, "The specified competition exists, but there are no results at this time.".to_string(), State::Error);
+// Keep the IJS results files that should be counted. When counting final results only, the
+// segments of an event with final results are dropped. Events without final results yet keep
+// their segments, unchecked when there are several so nothing is counted twice by accident.
+// Returns a note for the operator if any event is still missing its final results.
+pub fn apply_ijs_results_counting(events: Vec<Event>, counting: IjsResultsCounting) -> (Vec<Event>, Option<String>) {
+    let is_ijs = |event: &Event| event.scoring_system == ScoringSystem::IJS;
+
+    match counting {
+        IjsResultsCounting::PerSegment => {
+            let events = events
+                .into_iter()
+                .filter(|event| !is_ijs(event) || event.results_kind == ResultsKind::Segment)
+                .collect();
+
+            (events, None)
+        }
+        IjsResultsCounting::FinalResultsOnly => {
+            let categories = events
+                .iter()
+                .filter(|event| is_ijs(event) && event.results_kind == ResultsKind::Category)
+                .map(|event| event.category())
+                .collect::<Vec<String>>();
+
+            let mut events = events
+                .into_iter()
+                .filter(|event| !is_ijs(event) || event.results_kind == ResultsKind::Category || !categories.contains(&event.category()))
+                .collect::<Vec<Event>>();
+
+            let segments = events
+                .iter()
+                .filter(|event| is_ijs(event) && event.results_kind == ResultsKind::Segment)
+                .map(|event| event.category())
+                .collect::<Vec<String>>();
+
+            let mut incomplete_categories = vec![];
+            for event in &mut events {
+                if is_ijs(event) && event.results_kind == ResultsKind::Segment {
+                    let category = event.category();
+                    if segments.iter().filter(|segment| **segment == category).count() > 1 {
+                        event.active = false;
+                        if !incomplete_categories.contains(&category) {
+                            incomplete_categories.push(category);
+                        }
+                    }
+                }
+            }
+
+            let note = if incomplete_categories.is_empty() {
+                None
+            } else {
+                Some(format!("{} IJS event(s) have no final results yet, their segments are unchecked.", incomplete_categories.len()))
+            };
+
+            (events, note)
+        }
+    }
+}
+
+// Case insensitive wildcard match where * matches any run of characters and ? matches one.
+pub fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<char>>();
+    let text = text.to_lowercase().chars().collect::<Vec<char>>();
+
+    let (mut p, mut t) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            last_star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = last_star {
+            // Let the last star swallow one more character and try again.
+            p = star_p + 1;
+            t = star_t + 1;
+            last_star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::html::parser::State;
+    use crate::io::html::scoring_system::ScoringSystem;
+    use super::{apply_ijs_results_counting, retrieve_events, Event, IjsResultsCounting, ResultsKind};
+
+    fn fixture_events() -> Vec<Event> {
+        let (events, diagnostics, _, state) = retrieve_events(format!("{}/tests/fixtures/ijs", env!("CARGO_MANIFEST_DIR")));
+
+        assert!(diagnostics.is_empty());
+        assert!(matches!(state, State::Ok));
+        events
+    }
+
+    fn names(events: &[Event]) -> Vec<&str> {
+        events.iter().map(|event| event.event_name().as_str()).collect()
     }
 
-    (event_names, format!("Found {} IJS events and {} 6.0 events.", events_ijs.read().unwrap().len(), events_60.read().unwrap().len()), State::Ok)
-}
\ No newline at end of file
+    #[test]
+    fn final_results_and_segments_are_found_whatever_the_case_of_the_file_name() {
+        let events = fixture_events();
+
+        assert_eq!(names(&events), vec!["Junior Pairs", "Junior Pairs - Free Skate", "Junior Pairs - Short Program"]);
+        assert_eq!(events.iter().map(|event| event.results_kind()).collect::<Vec<ResultsKind>>(), vec![ResultsKind::Category, ResultsKind::Segment, ResultsKind::Segment]);
+    }
+
+    #[test]
+    fn segments_are_grouped_under_their_event() {
+        let events = fixture_events();
+
+        assert!(events.iter().all(|event| event.category() == "Junior Pairs"));
+
+        let unnamed_segment = Event::new(String::from("Open Solo Dance"), String::new(), ScoringSystem::IJS, ResultsKind::Segment, true);
+        assert_eq!(unnamed_segment.category(), "Open Solo Dance");
+    }
+
+    #[test]
+    fn counting_per_segment_drops_the_final_results() {
+        let (events, note) = apply_ijs_results_counting(fixture_events(), IjsResultsCounting::PerSegment);
+
+        assert_eq!(names(&events), vec!["Junior Pairs - Free Skate", "Junior Pairs - Short Program"]);
+        assert!(events.iter().all(|event| event.active()));
+        assert_eq!(note, None);
+    }
+
+    #[test]
+    fn counting_final_results_drops_the_segments_of_finished_events() {
+        let (events, note) = apply_ijs_results_counting(fixture_events(), IjsResultsCounting::FinalResultsOnly);
+
+        assert_eq!(names(&events), vec!["Junior Pairs"]);
+        assert!(events[0].active());
+        assert_eq!(note, None);
+    }
+
+    #[test]
+    fn counting_final_results_unchecks_the_segments_of_unfinished_events() {
+        let segments = fixture_events().into_iter().filter(|event| event.results_kind() == ResultsKind::Segment).collect();
+        let (events, note) = apply_ijs_results_counting(segments, IjsResultsCounting::FinalResultsOnly);
+
+        assert_eq!(names(&events), vec!["Junior Pairs - Free Skate", "Junior Pairs - Short Program"]);
+        assert!(events.iter().all(|event| !event.active()));
+        assert_eq!(note, Some(String::from("1 IJS event(s) have no final results yet, their segments are unchecked.")));
+    }
+}
@@ -1,9 +1,24 @@
 use std::fs;
 use crate::io::file_utils;
-use crate::io::html::club_points::ClubPoints;
+use crate::io::html::club_points::{subtotal_disciplines, ClubPoints};
+use crate::io::html::medal_table::{medal_ranks, medal_table_places, MedalCount, PLACE_NAMES};
+use crate::io::html::participation::awards_participation_points;
+use crate::io::html::results_sorter::shared_ranks;
+use crate::io::rounding::display_points;
 use crate::settings::settings::Settings;
 
-pub fn create_html(club_points: &Vec<ClubPoints>, settings: Settings, competition_name: &String) {
+pub fn create_html(club_points: &Vec<ClubPoints>, team_points: &Vec<ClubPoints>, medal_counts: &[MedalCount], settings: Settings, competition_name: &String) {
+    let mut tables = vec![];
+    if settings.standings_mode.includes_points() {
+        tables.push(generate_points_table(club_points, &settings, "Club"));
+        if !team_points.is_empty() {
+            tables.push(generate_points_table(team_points, &settings, "Team"));
+        }
+    }
+    if settings.standings_mode.includes_medals() {
+        tables.push(generate_medal_table(medal_counts, &settings));
+    }
+
     let html = format!(
         r#"<!DOCTYPE html>
 <html>
@@ -25,28 +40,72 @@ pub fn create_html(club_points: &Vec<ClubPoints>, settings: Settings, competitio
 
 </head>
 <body>
-<table style="width:50%">
-  <tr>
-    <th>#</th>
-    <th>Club</th>
-    <th>Points</th>
-  </tr>
-{table_contents}
-</table>
+{tables}
 </body>"#,
         competition_name=competition_name,
-        table_contents=generate_club_points_table(&club_points),
+        tables=tables.join("\n<br>\n"),
     );
 
     file_utils::check_and_create_dir(&settings.output_directory);
     fs::write(settings.output_directory + "/team_points.html", html).expect("Failed to write team_points.html");
 }
 
-fn generate_club_points_table(club_points: &Vec<ClubPoints>) -> String {
+// The points standings of the clubs, or of the teams they are grouped into, as named by the header.
+fn generate_points_table(club_points: &Vec<ClubPoints>, settings: &Settings, name_header: &str) -> String {
+    format!(
+        r#"<table style="width:50%">
+  <tr>
+    <th>#</th>
+    <th>{name_header}</th>
+{participation_header}    <th>Points</th>
+{discipline_headers}  </tr>
+{table_contents}
+</table>"#,
+        name_header=name_header,
+        participation_header=if awards_participation_points(settings) { "    <th>Participation</th>\n" } else { "" },
+        discipline_headers=subtotal_disciplines(club_points, settings).iter().map(|discipline| format!("    <th>{}</th>\n", discipline.get_name())).collect::<String>(),
+        table_contents=generate_club_points_table(club_points, settings),
+    )
+}
+
+fn generate_medal_table(medal_counts: &[MedalCount], settings: &Settings) -> String {
+    let places = medal_table_places(settings);
+
+    let mut header = String::from("  <tr>\n    <th>#</th>\n    <th>Club</th>\n");
+    for place_name in PLACE_NAMES.iter().take(places) {
+        header.push_str(format!("    <th>{}</th>\n", place_name).as_str());
+    }
+    header.push_str("    <th>Total</th>\n  </tr>\n");
+
+    let ranks = medal_ranks(medal_counts);
+    let mut rows = String::new();
+    for (i, medal_count) in medal_counts.iter().enumerate() {
+        rows.push_str(format!("  <tr>\n    <td>{}</td>\n    <td>{}</td>\n", ranks[i], medal_count.club()).as_str());
+        for medals in medal_count.medals() {
+            rows.push_str(format!("    <td>{}</td>\n", medals).as_str());
+        }
+        rows.push_str(format!("    <td>{}</td>\n  </tr>\n", medal_count.total()).as_str());
+    }
+
+    format!("<table style=\"width:50%\">\n{}{}</table>", header, rows)
+}
+
+fn generate_club_points_table(club_points: &Vec<ClubPoints>, settings: &Settings) -> String {
+    let ranks = shared_ranks(club_points, &settings.standings_tie_breakers);
+    let disciplines = subtotal_disciplines(club_points, settings);
     let mut club_rows: String = String::new();
     for club_points in club_points.into_iter().enumerate() {
         let (placement, club_points) = club_points;
-        club_rows.push_str(format!("  <tr>\n    <td>{}</td>\n    <td>{}</td>\n    <td>{}</td>\n  </tr>\n", placement + 1, club_points.club(), club_points.calc_total()).as_str());
+        let participation = if awards_participation_points(settings) {
+            format!("    <td>{}</td>\n", display_points(club_points.points_participation().unwrap_or_default(), settings))
+        } else {
+            String::new()
+        };
+        let subtotals = disciplines
+            .iter()
+            .map(|discipline| format!("    <td>{}</td>\n", display_points(club_points.points_discipline(*discipline), settings)))
+            .collect::<String>();
+        club_rows.push_str(format!("  <tr>\n    <td>{}</td>\n    <td>{}</td>\n{}    <td>{}</td>\n{}  </tr>\n", ranks[placement], club_points.club(), participation, display_points(club_points.calc_total(), settings), subtotals).as_str());
     }
 
     club_rows
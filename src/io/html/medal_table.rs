@@ -0,0 +1,160 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::club_points::truncated_club_merges;
+use crate::io::html::result_set::ResultSet;
+use crate::settings::settings::Settings;
+
+// The names of the places a medal table can count, first place first.
+pub const PLACE_NAMES: [&str; 6] = ["Gold", "Silver", "Bronze", "4th", "5th", "6th"];
+
+// Which standings are tabulated and written to the output files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StandingsMode {
+    // Clubs ranked by their points totals.
+    #[default]
+    Points,
+    // The points standings along with a medal table.
+    PointsAndMedals,
+    // Clubs ranked by their medals alone.
+    Medals,
+}
+
+impl StandingsMode {
+    pub fn includes_points(&self) -> bool {
+        *self != StandingsMode::Medals
+    }
+
+    pub fn includes_medals(&self) -> bool {
+        *self != StandingsMode::Points
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            StandingsMode::Points => String::from("Points"),
+            StandingsMode::PointsAndMedals => String::from("Points and Medals"),
+            StandingsMode::Medals => String::from("Medals"),
+        }
+    }
+}
+
+// How many times a club's entries finished in each counted place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MedalCount {
+    club: String,
+    medals: Vec<u64>,
+}
+
+impl MedalCount {
+    pub fn new(club: String, places: usize) -> Self {
+        Self {
+            club,
+            medals: vec![0; places],
+        }
+    }
+
+    pub fn club(&self) -> &String {
+        &self.club
+    }
+
+    // The number of finishes in each counted place, first place first.
+    pub fn medals(&self) -> &Vec<u64> {
+        &self.medals
+    }
+
+    pub fn total(&self) -> u64 {
+        self.medals.iter().sum()
+    }
+}
+
+// The number of places the medal table counts, from the podium up to sixth place.
+pub fn medal_table_places(settings: &Settings) -> usize {
+    settings.medal_table_places.clamp(3, PLACE_NAMES.len() as u64) as usize
+}
+
+// Count the counted places of every club and rank the clubs by golds, then silvers and so on.
+// Entries from several clubs earn a medal for each club they are credited to.
+pub fn medal_table(results_sets: &[ResultSet], settings: &Settings) -> Vec<MedalCount> {
+    let places = medal_table_places(settings);
+    let mut medal_counts: HashMap<String, MedalCount> = HashMap::new();
+
+    for results_set in results_sets.iter().filter(|results_set| results_set.is_placed()) {
+        let place = match results_set.rank {
+            Some(rank) if rank >= 1 && rank as usize <= places => rank as usize - 1,
+            _ => continue,
+        };
+
+        for (club, _) in settings.cross_club_credit.credits(results_set, Decimal::ONE) {
+            medal_counts.entry(club.clone()).or_insert_with(|| MedalCount::new(club, places)).medals[place] += 1;
+        }
+    }
+
+    if settings.attempt_automatic_60_club_name_recombination {
+        combine_truncated_clubs(&mut medal_counts);
+    }
+
+    let mut medal_counts = medal_counts.into_values().collect::<Vec<MedalCount>>();
+    medal_counts.sort_by(|a, b| compare_medals(a, b).then_with(|| a.club.cmp(&b.club)));
+
+    medal_counts
+}
+
+// Add the medals of clubs whose names 6.0 results cut short, e.g. "Skating Club of Bo...", to
+// the club with the full name, as is done for the points.
+fn combine_truncated_clubs(medal_counts: &mut HashMap<String, MedalCount>) {
+    let truncated_clubs = truncated_club_merges(&medal_counts.keys().cloned().collect::<Vec<String>>());
+
+    for (truncated_club, full_club) in truncated_clubs {
+        if let Some(truncated) = medal_counts.remove(&truncated_club) {
+            if let Some(full) = medal_counts.get_mut(&full_club) {
+                for (medals, truncated_medals) in full.medals.iter_mut().zip(truncated.medals) {
+                    *medals += truncated_medals;
+                }
+            }
+        }
+    }
+}
+
+// Which of two clubs ranks higher in the medal table.
+fn compare_medals(a: &MedalCount, b: &MedalCount) -> Ordering {
+    b.medals.cmp(&a.medals)
+}
+
+// The rank of each club in a sorted medal table. Clubs with the same medals share a rank.
+pub fn medal_ranks(medal_counts: &[MedalCount]) -> Vec<u64> {
+    let mut ranks: Vec<u64> = vec![];
+
+    for (i, medal_count) in medal_counts.iter().enumerate() {
+        let rank = match i {
+            0 => 1,
+            _ if compare_medals(&medal_counts[i - 1], medal_count) == Ordering::Equal => ranks[i - 1],
+            _ => i as u64 + 1,
+        };
+        ranks.push(rank);
+    }
+
+    ranks
+}
@@ -1,8 +1,20 @@
+pub mod best_results;
+pub mod club_matcher;
 pub mod club_points;
+pub mod competitor_status;
+pub mod discipline;
 pub mod html_writer;
+pub mod parse_error;
 pub mod parser;
+pub mod participation;
 pub mod results_sorter;
 pub mod result_set;
 pub mod scoring_system;
-pub(crate) mod event;
-mod points_system;
\ No newline at end of file
+pub mod team_entry;
+pub mod team_standings;
+pub mod tie_policy;
+pub mod event;
+pub mod medal_table;
+pub mod points_formula;
+pub mod points_rule;
+pub mod points_system;
\ No newline at end of file
@@ -0,0 +1,130 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::fmt;
+
+// Everything that can go wrong while reading a competition's results. Errors are collected as
+// diagnostics so a single bad event is skipped instead of stopping the whole tabulation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    // A directory or results file could not be read.
+    UnreadableFile {
+        file_path: String,
+        reason: String,
+    },
+    // An element did not have the structure the parser expects.
+    UnexpectedMarkup {
+        file_path: String,
+        event: Option<String>,
+        element: String,
+        reason: String,
+    },
+    // A rank cell held something other than a placement.
+    InvalidRank {
+        file_path: String,
+        event: Option<String>,
+        element: String,
+        reason: String,
+    },
+}
+
+impl ParseError {
+    pub fn unreadable_file(file_path: &str, reason: impl ToString) -> Self {
+        ParseError::UnreadableFile {
+            file_path: file_path.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn unexpected_markup(file_path: &str, event: Option<&String>, element: &str, reason: impl ToString) -> Self {
+        ParseError::UnexpectedMarkup {
+            file_path: file_path.to_string(),
+            event: event.cloned(),
+            element: element.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_rank(file_path: &str, event: Option<&String>, element: &str, reason: impl ToString) -> Self {
+        ParseError::InvalidRank {
+            file_path: file_path.to_string(),
+            event: event.cloned(),
+            element: element.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn file_path(&self) -> &String {
+        match self {
+            ParseError::UnreadableFile { file_path, .. } => file_path,
+            ParseError::UnexpectedMarkup { file_path, .. } => file_path,
+            ParseError::InvalidRank { file_path, .. } => file_path,
+        }
+    }
+
+    pub fn event(&self) -> Option<&String> {
+        match self {
+            ParseError::UnreadableFile { .. } => None,
+            ParseError::UnexpectedMarkup { event, .. } => event.as_ref(),
+            ParseError::InvalidRank { event, .. } => event.as_ref(),
+        }
+    }
+
+    pub fn element(&self) -> Option<&String> {
+        match self {
+            ParseError::UnreadableFile { .. } => None,
+            ParseError::UnexpectedMarkup { element, .. } => Some(element),
+            ParseError::InvalidRank { element, .. } => Some(element),
+        }
+    }
+
+    pub fn reason(&self) -> &String {
+        match self {
+            ParseError::UnreadableFile { reason, .. } => reason,
+            ParseError::UnexpectedMarkup { reason, .. } => reason,
+            ParseError::InvalidRank { reason, .. } => reason,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self.event() {
+            Some(event) => format!("{} ({})", event, self.file_path()),
+            None => self.file_path().clone(),
+        };
+
+        match self {
+            ParseError::UnreadableFile { reason, .. } => {
+                write!(f, "Could not read {}: {}", source, reason)
+            }
+            ParseError::UnexpectedMarkup { element, reason, .. } => {
+                write!(f, "Unexpected markup in {} at {}: {}", source, element, reason)
+            }
+            ParseError::InvalidRank { element, reason, .. } => {
+                write!(f, "Invalid rank in {} at {}: {}", source, element, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -24,11 +24,18 @@ use std::{fs, thread};
 use std::sync::{Arc, mpsc};
 use scraper::{CaseSensitivity, Element, ElementRef, Html, Selector};
 use scraper::selector::CssLocalName;
-use crate::io::html::club_points::{ClubPoints, sum_results};
-use crate::io::html::event::Event;
-use crate::io::html::result_set::{clean_club_names, ResultSet};
+use crate::io::club_directory::{apply_official_names, unknown_clubs, ClubDirectory};
+use crate::io::roster::{apply_roster, unmatched_skaters, Roster};
+use crate::io::html::club_points::{ClubPoints, small_field_events, sum_results};
+use crate::io::html::competitor_status::CompetitorStatus;
+use crate::io::html::event::{Event, ResultsKind};
+use crate::io::html::parse_error::ParseError;
+use crate::io::html::points_system;
+use crate::io::html::result_set::{clean_club_names, count_participants, ResultSet};
 use crate::io::html::results_sorter;
 use crate::io::html::scoring_system::ScoringSystem::{IJS, SixO};
+use crate::io::html::team_standings::team_points;
+use crate::io::html::team_entry::{combine_member_names, fill_missing_member_clubs, TeamMember};
 use crate::settings::settings::Settings;
 
 pub enum State {
@@ -36,27 +43,118 @@ pub enum State {
     Error,
 }
 
-// Parse results from a list of events according to the user's settings.
-pub fn parse_results(events: Vec<Event>, settings: &Settings) -> (Vec<ClubPoints>, Vec<ResultSet>, String, State) {
+// The results parsed from a list of events, along with the errors of the events that were skipped.
+pub type ParsedResults = (Vec<ResultSet>, Vec<ParseError>);
+
+// The club and team totals tabulated from a competition's results, along with the results, the
+// errors of the events that were skipped, a status message and whether the totals were calculated.
+pub type Tabulation = (Vec<ClubPoints>, Vec<ClubPoints>, Vec<ResultSet>, Vec<ParseError>, String, State);
+
+// Parse results from a list of events according to the user's settings. Events that cannot be
+// parsed are skipped and reported in the returned diagnostics.
+pub fn parse_results(events: Vec<Event>, settings: &Settings) -> Tabulation {
     // Split the event list into IJS and 6.0 components.
     let (events_ijs, events_60) = separate_events_by_scoring_system(&events);
 
     // Get the results from each event.
-    let (results_ijs, results_60) = calculate_raw_results(events_ijs, events_60);
+    let ((results_ijs, errors_ijs), (results_60, errors_60)) = calculate_raw_results(events_ijs, events_60);
+
+    let mut diagnostics = errors_ijs;
+    diagnostics.extend(errors_60);
 
     // Combine the results from IJS and 6.0 events.
-    let combined_raw_results = combine_raw_results(results_ijs, results_60);
+    let mut combined_raw_results = combine_raw_results(results_ijs, results_60, settings);
+
+    // Fill in or correct the club of every skater on the skater roster.
+    let roster = match Roster::from_settings(settings) {
+        Ok(roster) => roster,
+        Err(err) => return (vec![], vec![], combined_raw_results, diagnostics, err, State::Error),
+    };
+    if let Some(roster) = &roster {
+        apply_roster(&mut combined_raw_results, roster, settings);
+    }
+
+    // Use the official name of every club the club directory lists.
+    let directory = match ClubDirectory::from_settings(settings) {
+        Ok(directory) => directory,
+        Err(err) => return (vec![], vec![], combined_raw_results, diagnostics, err, State::Error),
+    };
+    if let Some(directory) = &directory {
+        apply_official_names(&mut combined_raw_results, directory);
+    }
+
+    // Work out the field size of each event from the statuses that count toward it.
+    count_participants(&mut combined_raw_results, &settings.statuses_counted_in_field_size);
+
+    let points_system = match points_system::from_settings(settings) {
+        Ok(points_system) => points_system,
+        Err(err) => return (vec![], vec![], combined_raw_results, diagnostics, err, State::Error),
+    };
 
     // Sum the results for every club.
-    let mut results = sum_results(&combined_raw_results, settings.clone())
-        .iter()
-        .map(|(_, club_points)| club_points.clone())
-        .collect();
+    let club_points = match sum_results(&combined_raw_results, points_system.as_ref(), settings.clone()) {
+        Ok(club_points) => club_points,
+        Err(err) => return (vec![], vec![], combined_raw_results, diagnostics, err, State::Error),
+    };
+
+    let mut results: Vec<ClubPoints> = club_points.into_values().collect();
+
+    if let Some(directory) = &directory {
+        for club_points in &mut results {
+            club_points.set_directory_club(directory.find(club_points.club()).cloned());
+        }
+    }
 
     // Sort the results.
-    results_sorter::sort_results(&mut results);
+    if !results.is_empty() {
+        results_sorter::sort_results(&mut results, &settings.standings_tie_breakers);
+    }
 
-    (results, combined_raw_results, String::from("Results Successfully Calculated"), State::Ok)
+    // Sum the results for every team.
+    let teams = match team_points(&combined_raw_results, &results, points_system.as_ref(), settings) {
+        Ok(teams) => teams,
+        Err(err) => return (vec![], vec![], combined_raw_results, diagnostics, err, State::Error),
+    };
+
+    let mut status = if diagnostics.is_empty() {
+        String::from("Results Successfully Calculated")
+    } else {
+        format!("Results Calculated, {} event(s) could not be read", diagnostics.len())
+    };
+
+    let small_fields = small_field_events(&combined_raw_results, settings);
+    if !small_fields.is_empty() {
+        status = format!(
+            "{}. {} event(s) below the minimum field size of {}: {}",
+            status,
+            small_fields.len(),
+            settings.participant_quantity_exclusion_point,
+            small_fields.join(", "),
+        );
+    }
+
+    let substituted = points_system::substituted_events(points_system.as_ref(), &combined_raw_results);
+    if !substituted.is_empty() {
+        status = format!(
+            "{}. {} event(s) scored from another chart column ({}): {}",
+            status,
+            substituted.len(),
+            settings.chart_resolution.get_name(),
+            substituted.join(", "),
+        );
+    }
+
+    let unmatched = unmatched_skaters(&combined_raw_results);
+    if !unmatched.is_empty() {
+        status = format!("{}. {} skater(s) not matched to the skater roster: {}", status, unmatched.len(), unmatched.join(", "));
+    }
+
+    let unknown = unknown_clubs(&results, settings);
+    if !unknown.is_empty() {
+        status = format!("{}. {} club(s) not in the club directory: {}", status, unknown.len(), unknown.join(", "));
+    }
+
+    (results, teams, combined_raw_results, diagnostics, status, State::Ok)
 }
 
 fn separate_events_by_scoring_system(events: &Vec<Event>) -> (Arc<Vec<Event>>, Arc<Vec<Event>>) {
@@ -79,9 +177,9 @@ fn separate_events_by_scoring_system(events: &Vec<Event>) -> (Arc<Vec<Event>>, A
     (events_ijs, events_60)
 }
 
-fn calculate_raw_results(events_ijs: Arc<Vec<Event>>, events_60: Arc<Vec<Event>>) -> (Vec<ResultSet>, Vec<ResultSet>) {
+fn calculate_raw_results(events_ijs: Arc<Vec<Event>>, events_60: Arc<Vec<Event>>) -> (ParsedResults, ParsedResults) {
     // Create senders and receivers to multithread the operations.
-    let (results_ijs_sender, results_ijs_receiver) = mpsc::channel::<Vec<ResultSet>>();
+    let (results_ijs_sender, results_ijs_receiver) = mpsc::channel::<ParsedResults>();
 
     // Use an Arc to atomically send data to a new thread and parse the IJS events asynchronously.
     let events_ijs_clone = events_ijs.clone();
@@ -90,7 +188,7 @@ fn calculate_raw_results(events_ijs: Arc<Vec<Event>>, events_60: Arc<Vec<Event>>
     });
 
     // Create senders and receivers to multithread the operations.
-    let (results_60_sender, results_60_receiver) = mpsc::channel::<Vec<ResultSet>>();
+    let (results_60_sender, results_60_receiver) = mpsc::channel::<ParsedResults>();
 
     // Use an Arc to atomically send data to a new thread and parse the 6.0 events asynchronously.
     let events_60_clone = events_60.clone();
@@ -105,12 +203,13 @@ fn calculate_raw_results(events_ijs: Arc<Vec<Event>>, events_60: Arc<Vec<Event>>
     (results_ijs, results_60)
 }
 
-// Combine both IJS and 6.0 result sets into one and clean up club names.
-fn combine_raw_results(results_ijs: Vec<ResultSet>, results_60: Vec<ResultSet>) -> Vec<ResultSet> {
+// Combine both IJS and 6.0 result sets into one and clean up club names, replacing any the user
+// has aliased to another club.
+fn combine_raw_results(results_ijs: Vec<ResultSet>, results_60: Vec<ResultSet>, settings: &Settings) -> Vec<ResultSet> {
     let mut combined_raw_results = results_ijs;
     combined_raw_results.extend(results_60);
 
-    clean_club_names(&mut combined_raw_results);
+    clean_club_names(&mut combined_raw_results, &settings.club_aliases);
 
     combined_raw_results
 }
@@ -142,12 +241,97 @@ pub(crate) fn clean_event_names(mut event_names: Vec<Event>) -> Vec<Event> {
     event_names
 }
 
-// Parse IJS events from a list of files.
-pub(crate) fn parse_ijs_event_names(ijs_events: &Vec<String>) -> Vec<Event> {
+// Return the contents of an element's HTML between its opening markup and the closing markup.
+fn element_contents<'a>(html: &'a str, opening: &str, closing: &str) -> Option<&'a str> {
+    html.split(opening).nth(1)?.split(closing).next()
+}
+
+// Read the contents of a rank cell, which is either a placement such as "1" or "1." or a status
+// such as "WD" or "DNS" for skaters who were not placed.
+fn set_rank_or_status(result_set: &mut ResultSet, rank: &str, file_path: &str, event_name: &String, element: &str) -> Result<(), ParseError> {
+    match rank.trim().trim_end_matches('.').parse() {
+        Ok(n) => {
+            result_set.rank = Some(n);
+            result_set.status = CompetitorStatus::Placed;
+        }
+        Err(err) => match CompetitorStatus::from_rank_text(rank) {
+            Some(status) => {
+                result_set.rank = None;
+                result_set.status = status;
+            }
+            None => return Err(ParseError::invalid_rank(file_path, Some(event_name), element, format!("\"{}\" is not a placement or a status ({})", rank.trim(), err))),
+        },
+    }
+
+    Ok(())
+}
+
+// Parse the skaters of an entry, each line given as "Name, Club", "A / B, Club" or
+// "A / B, Club A / Club B". A skater listed without a club shares the club of their partner.
+fn parse_members<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<TeamMember> {
+    let mut members = lines
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .flat_map(parse_member_line)
+        .collect::<Vec<TeamMember>>();
+
+    fill_missing_member_clubs(&mut members);
+
+    members
+}
+
+fn parse_member_line(line: &str) -> Vec<TeamMember> {
+    let mut name_field_split = line.split(", ");
+    let names = name_field_split.next().unwrap_or("");
+    let clubs = name_field_split.next().unwrap_or("");
+
+    let names = names.split(" / ").map(|name| name.trim()).collect::<Vec<&str>>();
+
+    // A single skater keeps the club exactly as written, even if it contains a slash.
+    if names.len() < 2 {
+        return vec![TeamMember::new(names.concat(), String::from(clubs))];
+    }
+
+    let clubs = clubs.split(" / ").map(|club| club.trim()).collect::<Vec<&str>>();
+
+    names.iter().enumerate().map(|(i, name)| {
+        let club = if clubs.len() == 1 {
+            clubs[0]
+        } else {
+            clubs.get(i).copied().unwrap_or("")
+        };
+
+        TeamMember::new(String::from(*name), String::from(club))
+    }).collect()
+}
+
+// Set the name and club of a result from its skaters. Team entries keep every skater as a member
+// and are listed under the club of the first skater.
+fn set_name_and_club(result_set: &mut ResultSet, mut members: Vec<TeamMember>) {
+    if members.len() > 1 {
+        result_set.name = Some(combine_member_names(&members));
+        result_set.club = Some(members[0].club().clone());
+        result_set.members = members;
+    } else {
+        let member = members.pop().unwrap_or_else(|| TeamMember::new(String::new(), String::new()));
+        result_set.name = Some(member.name);
+        result_set.club = Some(member.club);
+    }
+}
+
+// Parse IJS events from a list of files holding either final or segment results.
+pub(crate) fn parse_ijs_event_names(ijs_events: &Vec<String>, results_kind: ResultsKind) -> (Vec<Event>, Vec<ParseError>) {
     let mut event_names = vec![];
+    let mut errors = vec![];
 
     for results_file_path in ijs_events {
-        let results_file_contents = fs::read_to_string(results_file_path).unwrap();
+        let results_file_contents = match fs::read_to_string(results_file_path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                errors.push(ParseError::unreadable_file(results_file_path, err));
+                continue;
+            }
+        };
         let document = Html::parse_document(&results_file_contents);
         let selector = Selector::parse(r#"body > h2"#).unwrap();
 
@@ -155,33 +339,37 @@ pub(crate) fn parse_ijs_event_names(ijs_events: &Vec<String>) -> Vec<Event> {
         let document_select_collection = document_select.collect::<Vec<ElementRef>>();
         for element in document_select_collection.clone().into_iter().enumerate() {
             if element.1.has_class(&CssLocalName::from("catseg"), CaseSensitivity::CaseSensitive) {
-                let event_name = match
-                String::from(element.1.html()
-                    .split("<h2 class=\"catseg\">")
-                    .nth(1)
-                    .unwrap()
-                    .split("</h2>")
-                    .nth(0)
-                    .unwrap()
-                ).parse() {
-                    Ok(n) => n,
-                    Err(err) => panic!("{}", err),
+                let element_html = element.1.html();
+                let event_name = match element_contents(&element_html, "<h2 class=\"catseg\">", "</h2>") {
+                    Some(event_name) => event_name.to_string(),
+                    None => {
+                        errors.push(ParseError::unexpected_markup(results_file_path, None, "h2.catseg", "the event name could not be found"));
+                        continue;
+                    }
                 };
 
-                event_names.push(Event::new(event_name, results_file_path.clone(), IJS, true));
+                event_names.push(Event::new(event_name, results_file_path.clone(), IJS, results_kind, true));
                 continue;
             }
         }
     }
 
-    event_names
+    (event_names, errors)
 }
 
 // Parse 6.0 events from a list of files.
-pub(crate) fn parse_60_event_names(ijs_events: &Vec<String>) -> Vec<Event> {
+pub(crate) fn parse_60_event_names(ijs_events: &Vec<String>) -> (Vec<Event>, Vec<ParseError>) {
     let mut event_names = vec![];
+    let mut errors = vec![];
+
     for results_file_path in ijs_events {
-        let results_file_contents = fs::read_to_string(results_file_path).unwrap();
+        let results_file_contents = match fs::read_to_string(results_file_path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                errors.push(ParseError::unreadable_file(results_file_path, err));
+                continue;
+            }
+        };
         let document = Html::parse_document(&results_file_contents);
         let selector = Selector::parse(r#"table > caption > h2"#).unwrap();
 
@@ -189,192 +377,297 @@ pub(crate) fn parse_60_event_names(ijs_events: &Vec<String>) -> Vec<Event> {
         let document_select_collection = document_select.collect::<Vec<ElementRef>>();
         for element in document_select_collection.clone().into_iter().enumerate() {
             if element.1.html().starts_with("<h2>") {
-                let next_element = document_select_collection.get(element.0 + 1).unwrap();
+                // The first heading is the competition, the event is in the heading after it.
+                let next_element = match document_select_collection.get(element.0 + 1) {
+                    Some(next_element) => next_element,
+                    None => {
+                        errors.push(ParseError::unexpected_markup(results_file_path, None, "caption > h2", "no event heading follows the competition heading"));
+                        break;
+                    }
+                };
+
                 if next_element.html().starts_with("<h2>") {
-                    let event_name = match
-                    String::from(next_element.html()
-                        .split("<h2>")
-                        .nth(1)
-                        .unwrap()
-                        .split("</h2>")
-                        .nth(0)
-                        .unwrap()
-                    ).parse() {
-                        Ok(n) => n,
-                        Err(err) => panic!("{}", err),
+                    let next_element_html = next_element.html();
+                    let event_name = match element_contents(&next_element_html, "<h2>", "</h2>") {
+                        Some(event_name) => event_name.to_string(),
+                        None => {
+                            errors.push(ParseError::unexpected_markup(results_file_path, None, "caption > h2", "the event name could not be found"));
+                            break;
+                        }
                     };
 
-                    event_names.push(Event::new(event_name, results_file_path.clone(), SixO, true));
+                    event_names.push(Event::new(event_name, results_file_path.clone(), SixO, ResultsKind::Category, true));
                     break;
                 }
             }
         }
     }
 
-    event_names
+    (event_names, errors)
 }
 
 // Parse IJS results from a list of events.
-pub fn parse_ijs(ijs_events: Vec<Event>) -> Vec<ResultSet> {
+pub fn parse_ijs(ijs_events: Vec<Event>) -> ParsedResults {
     let mut results = vec![];
+    let mut errors = vec![];
 
     for event in ijs_events {
-        let results_file_contents = fs::read_to_string(event.file_path.clone()).unwrap();
-        let event_name = event.event_name.clone();
-        let document = Html::parse_document(&results_file_contents);
-        let selector = Selector::parse(r#"table > tbody > tr > td"#).unwrap();
+        match parse_ijs_event(&event) {
+            Ok(mut results_for_event) => results.append(&mut results_for_event),
+            Err(err) => errors.push(err),
+        }
+    }
 
-        let document_select = document.select(&selector);
-        let document_select_collection = document_select.collect::<Vec<ElementRef>>();
+    (results, errors)
+}
 
-        let mut results_for_event = vec![];
-        for element in document_select_collection.clone().into_iter().enumerate() {
-            let mut has_rank = false;
-            let mut result_set = ResultSet::new(IJS);
-            if element.1.has_class(&CssLocalName::from("rank"), CaseSensitivity::CaseSensitive) {
-                result_set.rank = Some(match
-                String::from(element.1.html()
-                    .split("<td class=\"rank\">")
-                    .nth(1)
-                    .unwrap()
-                    .split("</td>")
-                    .nth(0)
-                    .unwrap()
-                ).parse() {
-                    Ok(n) => n,
-                    Err(err) => panic!("{}", err),
-                }
-                );
-                has_rank = true;
-            }
+// Parse the results of a single IJS event. Any error skips the whole event.
+fn parse_ijs_event(event: &Event) -> Result<Vec<ResultSet>, ParseError> {
+    let file_path = event.file_path.as_str();
+    let event_name = event.event_name.clone();
+
+    let results_file_contents = match fs::read_to_string(file_path) {
+        Ok(contents) => contents,
+        Err(err) => return Err(ParseError::unreadable_file(file_path, err)),
+    };
+    let document = Html::parse_document(&results_file_contents);
+    let selector = Selector::parse(r#"table > tbody > tr > td"#).unwrap();
+
+    let document_select = document.select(&selector);
+    let document_select_collection = document_select.collect::<Vec<ElementRef>>();
+
+    let mut results_for_event = vec![];
+    for element in document_select_collection.clone().into_iter().enumerate() {
+        let mut result_set = ResultSet::new(IJS);
+        if !element.1.has_class(&CssLocalName::from("rank"), CaseSensitivity::CaseSensitive) {
+            continue;
+        }
 
-            if !has_rank { continue; };
-
-            let next_element = document_select_collection.get(element.0 + 1).unwrap();
-            if next_element.has_class(&CssLocalName::from("name"), CaseSensitivity::CaseSensitive) {
-                let next_element_html = next_element.html();
-                let mut name_field_split = next_element_html
-                    .split("<td class=\"name\">")
-                    .nth(1)
-                    .unwrap()
-                    .split("</td>")
-                    .nth(0)
-                    .unwrap()
-                    .split(", ");
-                result_set.name = Some(String::from(name_field_split.nth(0).unwrap_or(&String::from(""))));
-                result_set.club = Some(String::from(name_field_split.nth(0).unwrap_or(&String::from(""))));
-                result_set.event = Some(event_name.clone());
-            }
+        let element_html = element.1.html();
+        let rank = match element_contents(&element_html, "<td class=\"rank\">", "</td>") {
+            Some(rank) => rank.trim(),
+            None => return Err(ParseError::unexpected_markup(file_path, Some(&event_name), "td.rank", "the rank cell has no contents")),
+        };
 
-            results_for_event.push(result_set);
-        }
+        set_rank_or_status(&mut result_set, rank, file_path, &event_name, "td.rank")?;
 
-        let participants = results_for_event.len() as u64;
-        for result in results_for_event.iter_mut() {
-            result.participants = Some(participants);
+        let next_element = match document_select_collection.get(element.0 + 1) {
+            Some(next_element) => next_element,
+            None => return Err(ParseError::unexpected_markup(file_path, Some(&event_name), "td.rank", "the rank is not followed by a name")),
+        };
+
+        // A rank without a skater, e.g. in a row of subtotals, is no result.
+        if !next_element.has_class(&CssLocalName::from("name"), CaseSensitivity::CaseSensitive) {
+            continue;
         }
 
-        results.append(&mut results_for_event);
+        let next_element_html = next_element.html();
+        let name_field = match element_contents(&next_element_html, "<td class=\"name\">", "</td>") {
+            Some(name_field) => name_field,
+            None => return Err(ParseError::unexpected_markup(file_path, Some(&event_name), "td.name", "the name cell has no contents")),
+        };
+
+        // Pairs and dance couples are listed as "A / B, Club" or "A / B, Club A / Club B".
+        let members = parse_members(name_field.split("<br>"));
+        set_name_and_club(&mut result_set, members);
+        result_set.event = Some(event_name.clone());
+
+        results_for_event.push(result_set);
     }
 
-    results
+    Ok(results_for_event)
 }
 
 // Parse 6.0 results from a list of events.
-pub fn parse_60(events_60: Vec<Event>) -> Vec<ResultSet> {
+pub fn parse_60(events_60: Vec<Event>) -> ParsedResults {
     let mut results = vec![];
+    let mut errors = vec![];
+
     for event in events_60 {
-        let results_file_contents = fs::read_to_string(event.file_path.clone()).unwrap();
-        let event_name = event.event_name.clone();
-        let document = Html::parse_document(&results_file_contents);
-        let selector = Selector::parse(r#"table > tbody > tr > td"#).unwrap();
+        match parse_60_event(&event) {
+            Ok(mut results_for_event) => results.append(&mut results_for_event),
+            Err(err) => errors.push(err),
+        }
+    }
 
-        let document_select = document.select(&selector);
-        let document_select_collection = document_select.collect::<Vec<ElementRef>>();
+    (results, errors)
+}
 
-        let mut results_for_event = vec![];
-        for element in document_select_collection.clone().into_iter().enumerate() {
-            let mut has_name = false;
-            let mut result_set = ResultSet::new(SixO);
-
-            if element.1.html().contains("<td rowspan=\"1\" colspan=\"1\">") { // Name first this time because the name has more distinctive markings for it in the HTML.
-                let next_element_html = element.1.html();
-                let mut name_field_split = next_element_html
-                    .split("<td rowspan=\"1\" colspan=\"1\">")
-                    .nth(1)
-                    .unwrap()
-                    .split("</td>").nth(0)
-                    .unwrap()
-                    .split(", ");
-                let temp_name = String::from(name_field_split.nth(0).unwrap_or(""));
-                let temp_club = String::from(name_field_split.nth(0).unwrap_or(""));
-
-                if temp_club.contains("<br>") { // Handle duets and such by disregarding them. This is a terrible solution.
-                    continue;
-                }
+// Parse the results of a single 6.0 event. Any error skips the whole event.
+fn parse_60_event(event: &Event) -> Result<Vec<ResultSet>, ParseError> {
+    let file_path = event.file_path.as_str();
+    let event_name = event.event_name.clone();
+
+    let results_file_contents = match fs::read_to_string(file_path) {
+        Ok(contents) => contents,
+        Err(err) => return Err(ParseError::unreadable_file(file_path, err)),
+    };
+    let document = Html::parse_document(&results_file_contents);
+    let selector = Selector::parse(r#"table > tbody > tr > td"#).unwrap();
+
+    let document_select = document.select(&selector);
+    let document_select_collection = document_select.collect::<Vec<ElementRef>>();
+
+    let mut results_for_event = vec![];
+    for element in document_select_collection.clone().into_iter().enumerate() {
+        let mut result_set = ResultSet::new(SixO);
+
+        // Name first this time because the name has more distinctive markings for it in the HTML.
+        let element_html = element.1.html();
+        let name_cell_opening = if element_html.contains("<td rowspan=\"1\" colspan=\"1\">") {
+            "<td rowspan=\"1\" colspan=\"1\">"
+        } else if element_html.contains("<td colspan=\"1\" rowspan=\"1\">") {
+            "<td colspan=\"1\" rowspan=\"1\">"
+        } else {
+            continue;
+        };
+
+        let name_field = match element_contents(&element_html, name_cell_opening, "</td>") {
+            Some(name_field) => name_field,
+            None => return Err(ParseError::unexpected_markup(file_path, Some(&event_name), "td[rowspan][colspan]", "the name cell has no contents")),
+        };
+
+        // Duets, pairs and other team entries list one skater per line.
+        let members = parse_members(name_field.split("<br>"));
+        set_name_and_club(&mut result_set, members);
+        result_set.event = Some(event_name.clone());
+
+        let previous_element = match element.0.checked_sub(1).and_then(|i| document_select_collection.get(i)) {
+            Some(previous_element) => previous_element,
+            None => return Err(ParseError::unexpected_markup(file_path, Some(&event_name), "td", "the name is not preceded by a rank")),
+        };
+        let previous_element_html = previous_element.html();
+        let rank = match element_contents(&previous_element_html, "<td>", "</td>") {
+            Some(rank) => rank,
+            None => return Err(ParseError::unexpected_markup(file_path, Some(&event_name), "td", "the rank cell has no contents")),
+        };
+
+        // Skaters who withdrew or were not placed are listed with "&nbsp;" or a status instead.
+        set_rank_or_status(&mut result_set, rank, file_path, &event_name, "td")?;
+
+        results_for_event.push(result_set);
+    }
 
-                result_set.name = Some(temp_name);
-                result_set.club = Some(temp_club);
-                result_set.event = Some(event_name.clone());
-
-                has_name = true;
-            } else if element.1.html().contains("<td colspan=\"1\" rowspan=\"1\">") { // Name first this time because the name has more distinctive markings for it in the HTML.
-                let next_element_html = element.1.html();
-                let mut name_field_split = next_element_html
-                    .split("<td colspan=\"1\" rowspan=\"1\">")
-                    .nth(1)
-                    .unwrap()
-                    .split("</td>").nth(0)
-                    .unwrap()
-                    .split(", ");
-
-                let temp_name = String::from(name_field_split.nth(0).unwrap_or(""));
-                let temp_club = String::from(name_field_split.nth(0).unwrap_or(""));
-
-                if temp_club.contains("<br>") { // Handle duets and such by disregarding them. This is a terrible solution.
-                    continue;
-                }
+    Ok(results_for_event)
+}
 
-                result_set.name = Some(temp_name);
-                result_set.club = Some(temp_club);
-                result_set.event = Some(event_name.clone());
+#[cfg(test)]
+mod tests {
+    use crate::io::html::competitor_status::CompetitorStatus;
+    use crate::io::html::event::{Event, ResultsKind};
+    use crate::io::html::result_set::ResultSet;
+    use crate::io::html::scoring_system::ScoringSystem::{IJS, SixO};
+    use super::{parse_60_event, parse_60_event_names, parse_ijs_event, parse_ijs_event_names, parse_members, set_rank_or_status};
 
-                has_name = true;
-            }
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/ijs/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
 
-            if !has_name {
-                continue;
-            };
-
-            let next_element = document_select_collection.get(element.0 - 1).unwrap();
-            let temp_rank = String::from(next_element.html()
-                .split("<td>")
-                .nth(1)
-                .unwrap()
-                .split(".</td>")
-                .nth(0)
-                .unwrap()
-            );
-
-            if temp_rank.contains("&nbsp;") {
-                continue;
-            }
+    fn fixture_60(name: &str) -> String {
+        format!("{}/tests/fixtures/sixo/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
 
-            result_set.rank = Some(match temp_rank.parse() {
-                Ok(n) => n,
-                Err(err) => panic!("{}", err),
-            });
+    fn members(result_set: &ResultSet) -> Vec<(String, String)> {
+        result_set.members().iter().map(|member| (member.name().clone(), member.club().clone())).collect()
+    }
 
-            results_for_event.push(result_set);
-        }
+    fn member(name: &str, club: &str) -> (String, String) {
+        (String::from(name), String::from(club))
+    }
 
-        let participants = results_for_event.len() as u64;
-        for result in results_for_event.iter_mut() {
-            result.participants = Some(participants);
-        }
+    #[test]
+    fn category_page_heading_names_its_event() {
+        let (events, errors) = parse_ijs_event_names(&vec![fixture("CAT001RS.htm")], ResultsKind::Category);
+
+        assert!(errors.is_empty());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_name(), "Junior Pairs");
+        assert_eq!(events[0].results_kind(), ResultsKind::Category);
+    }
 
-        results.append(&mut results_for_event);
+    #[test]
+    fn category_page_results_are_read_like_a_segment() {
+        let event = Event::new(String::from("Junior Pairs"), fixture("CAT001RS.htm"), IJS, ResultsKind::Category, true);
+        let results = parse_ijs_event(&event).unwrap();
+
+        assert_eq!(results.iter().map(|result_set| result_set.rank()).collect::<Vec<u64>>(), vec![1, 2, 3, 0]);
+        assert!(results.iter().all(|result_set| result_set.event() == "Junior Pairs"));
+        assert_eq!(members(&results[0]), vec![member("Ann Archer", "Skating Club of Boston"), member("Ben Baker", "Skating Club of Boston")]);
+        assert_eq!(members(&results[1]), vec![member("Cate Cole", "Los Angeles FSC"), member("Dan Dale", "Los Angeles FSC")]);
+        assert_eq!(members(&results[2]), vec![member("Eva Eng", "Skating Club of Boston"), member("Finn Ford", "Los Angeles FSC")]);
+        assert_eq!(results[3].status(), CompetitorStatus::Withdrew);
     }
+    #[test]
+    fn partners_and_their_clubs_are_split_on_slashes() {
+        let members = parse_members(["Ann Archer / Ben Baker, Skating Club of Boston / Los Angeles FSC"].into_iter());
+
+        assert_eq!(members.iter().map(|member| (member.name().clone(), member.club().clone())).collect::<Vec<(String, String)>>(), vec![member("Ann Archer", "Skating Club of Boston"), member("Ben Baker", "Los Angeles FSC")]);
+    }
+
+    #[test]
+    fn partners_share_a_single_listed_club() {
+        let members = parse_members(["Ann Archer / Ben Baker, Skating Club of Boston"].into_iter());
 
-    results
-}
\ No newline at end of file
+        assert!(members.iter().all(|member| member.club() == "Skating Club of Boston"));
+        assert_eq!(members.len(), 2);
+    }
+
+    #[test]
+    fn single_skater_keeps_a_club_with_a_slash() {
+        let members = parse_members(["Ann Archer, Club A / Club B"].into_iter());
+
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].club(), "Club A / Club B");
+    }
+    #[test]
+    fn team_entries_on_60_pages_list_a_skater_per_line() {
+        let (events, errors) = parse_60_event_names(&vec![fixture_60("E02c1.htm")]);
+        assert!(errors.is_empty());
+        assert_eq!(events[0].event_name(), "Novice Shadow Dance");
+
+        let results = parse_60_event(&events[0]).unwrap();
+
+        assert_eq!(results.iter().map(|result_set| result_set.rank()).collect::<Vec<u64>>(), vec![1, 2, 3, 4]);
+        assert!(results.iter().all(|result_set| *result_set.scoring_system() == SixO));
+        assert_eq!(results[0].name(), "Ann Archer / Ben Baker");
+        assert_eq!(results[0].club(), "Skating Club of Boston");
+        assert_eq!(members(&results[2]), vec![member("Eva Eng", "Skating Club of Boston"), member("Finn Ford", "Los Angeles FSC")]);
+        assert_eq!(results[2].clubs(), vec![String::from("Skating Club of Boston"), String::from("Los Angeles FSC")]);
+    }
+
+    #[test]
+    fn skaters_listed_without_a_club_share_their_partners_club() {
+        let event = Event::new(String::from("Novice Shadow Dance"), fixture_60("E02c1.htm"), SixO, ResultsKind::Category, true);
+        let results = parse_60_event(&event).unwrap();
+
+        // The next skater's club is used first, then the previous skater's.
+        assert_eq!(members(&results[1]), vec![member("Cate Cole", "Los Angeles FSC"), member("Dan Dale", "Los Angeles FSC")]);
+        assert_eq!(members(&results[3]), vec![member("Gia Grey", "Skating Club of Boston"), member("Hal Hart", "Skating Club of Boston")]);
+    }
+    #[test]
+    fn skaters_who_were_not_placed_keep_their_status() {
+        let event = Event::new(String::from("Juvenile Girls"), fixture_60("E03c1.htm"), SixO, ResultsKind::Category, true);
+        let results = parse_60_event(&event).unwrap();
+
+        assert_eq!(results.iter().map(|result_set| result_set.status()).collect::<Vec<CompetitorStatus>>(), vec![
+            CompetitorStatus::Placed,
+            CompetitorStatus::Placed,
+            CompetitorStatus::Withdrew,
+            CompetitorStatus::DidNotStart,
+            CompetitorStatus::Disqualified,
+            CompetitorStatus::NoRank,
+        ]);
+        assert_eq!(results.iter().map(|result_set| result_set.rank()).collect::<Vec<u64>>(), vec![1, 2, 0, 0, 0, 0]);
+        assert_eq!(results[5].name(), "Nia Nash");
+    }
+
+    #[test]
+    fn rank_cell_that_is_neither_a_placement_nor_a_status_is_an_error() {
+        let mut result_set = ResultSet::new(SixO);
+        let event_name = String::from("Juvenile Girls");
+
+        assert!(set_rank_or_status(&mut result_set, "3.", "E03c1.htm", &event_name, "td").is_ok());
+        assert_eq!(result_set.rank(), 3);
+        assert!(set_rank_or_status(&mut result_set, "Judge 1", "E03c1.htm", &event_name, "td").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::collections::HashSet;
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::best_results::{member_identity, skaters_of};
+use crate::io::html::competitor_status::CompetitorStatus;
+use crate::io::html::result_set::ResultSet;
+use crate::settings::settings::Settings;
+
+// What earns a club participation points, on top of the points for placements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ParticipationBasis {
+    // Every entry that started an event, however it placed.
+    #[default]
+    #[serde(rename = "PerEntry")]
+    Entry,
+    // Every skater who started at least one event, once for the competition.
+    #[serde(rename = "PerSkater")]
+    Skater,
+    // Every skater who was placed in at least one event, once for the competition.
+    #[serde(rename = "PerFinisher")]
+    Finisher,
+}
+
+impl ParticipationBasis {
+    pub fn get_name(&self) -> String {
+        match self {
+            ParticipationBasis::Entry => String::from("Per Entry"),
+            ParticipationBasis::Skater => String::from("Per Skater"),
+            ParticipationBasis::Finisher => String::from("Per Finisher"),
+        }
+    }
+}
+
+// Whether an entry took part in its event. Withdrawn entries and skaters who did not start did not.
+fn started(result_set: &ResultSet) -> bool {
+    !matches!(result_set.status(), CompetitorStatus::Withdrew | CompetitorStatus::DidNotStart)
+}
+
+// The participation points each club earns, as the credits of each entry or skater that earns them.
+// Skaters are credited to their own club, once however many entries they skate in.
+pub fn participation_credits(results_sets: &[ResultSet], settings: &Settings) -> Vec<Vec<(String, Decimal)>> {
+    if settings.participation_points.is_zero() {
+        return vec![];
+    }
+
+    let mut skaters: HashSet<String> = HashSet::new();
+    let mut credits = vec![];
+
+    for results_set in results_sets {
+        let takes_part = match settings.participation_basis {
+            ParticipationBasis::Entry => {
+                if started(results_set) {
+                    credits.push(settings.cross_club_credit.credits(results_set, settings.participation_points));
+                }
+                continue;
+            }
+            ParticipationBasis::Skater => started(results_set),
+            ParticipationBasis::Finisher => results_set.is_placed(),
+        };
+
+        if !takes_part {
+            continue;
+        }
+
+        for (name, club) in skaters_of(results_set) {
+            if skaters.insert(member_identity(&name, &club)) {
+                credits.push(vec![(club, settings.participation_points)]);
+            }
+        }
+    }
+
+    credits
+}
+
+// Whether participation points are awarded, and so shown in their own column.
+pub fn awards_participation_points(settings: &Settings) -> bool {
+    !settings.participation_points.is_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use crate::io::html::result_set::ResultSet;
+    use crate::io::html::scoring_system::ScoringSystem;
+    use crate::io::html::team_entry::TeamMember;
+    use crate::settings::settings::Settings;
+    use super::{participation_credits, ParticipationBasis};
+
+    fn single(name: &str, club: &str) -> ResultSet {
+        let mut result_set = ResultSet::new(ScoringSystem::IJS);
+        result_set.name = Some(name.to_string());
+        result_set.club = Some(club.to_string());
+        result_set
+    }
+
+    fn pair(first: (&str, &str), second: (&str, &str)) -> ResultSet {
+        let mut result_set = ResultSet::new(ScoringSystem::IJS);
+        result_set.members = vec![
+            TeamMember::new(first.0.to_string(), first.1.to_string()),
+            TeamMember::new(second.0.to_string(), second.1.to_string()),
+        ];
+        result_set.name = Some(format!("{} / {}", first.0, second.0));
+        result_set.club = Some(first.1.to_string());
+        result_set
+    }
+
+    fn per_skater() -> Settings {
+        Settings {
+            participation_points: Decimal::ONE,
+            participation_basis: ParticipationBasis::Skater,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn basis_keeps_the_names_saved_in_settings_files() {
+        let settings: Settings = toml::from_str("participation_basis = \"PerFinisher\"").unwrap();
+
+        assert_eq!(settings.participation_basis, ParticipationBasis::Finisher);
+        assert!(toml::to_string(&settings).unwrap().contains("participation_basis = \"PerFinisher\""));
+    }
+
+    fn total(credits: &[Vec<(String, Decimal)>], club: &str) -> Decimal {
+        credits.iter().flatten().filter(|(name, _)| name == club).map(|(_, points)| *points).sum()
+    }
+
+    #[test]
+    fn pair_from_one_club_earns_a_credit_per_skater() {
+        let credits = participation_credits(&[pair(("Ann", "Club A"), ("Bob", "Club A"))], &per_skater());
+
+        assert_eq!(total(&credits, "Club A"), Decimal::from(2));
+    }
+
+    #[test]
+    fn skaters_of_a_mixed_pair_credit_their_own_clubs() {
+        let credits = participation_credits(&[pair(("Ann", "Club A"), ("Bob", "Club B"))], &per_skater());
+
+        assert_eq!(total(&credits, "Club A"), Decimal::ONE);
+        assert_eq!(total(&credits, "Club B"), Decimal::ONE);
+    }
+
+    #[test]
+    fn skater_in_singles_and_a_pair_is_credited_once() {
+        let results_sets = [single("Ann", "Club A"), pair(("Ann", "Club A"), ("Bob", "Club A"))];
+        let credits = participation_credits(&results_sets, &per_skater());
+
+        assert_eq!(total(&credits, "Club A"), Decimal::from(2));
+    }
+}
@@ -0,0 +1,456 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+// Points written as an expression of the skater's rank and the number of participants in the
+// event, e.g. "max(0, participants - rank + 1)". Supports + - * / ^, parentheses and the
+// functions min, max, abs, floor, ceil and round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointsFormula {
+    source: String,
+    expression: Expression,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expression {
+    Number(Decimal),
+    Rank,
+    Participants,
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    Function(Function, Vec<Expression>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Min,
+    Max,
+    Abs,
+    Floor,
+    Ceil,
+    Round,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "abs" => Some(Function::Abs),
+            "floor" => Some(Function::Floor),
+            "ceil" => Some(Function::Ceil),
+            "round" => Some(Function::Round),
+            _ => None,
+        }
+    }
+
+    // The smallest and largest number of arguments the function takes.
+    fn arity(&self) -> (usize, usize) {
+        match self {
+            Function::Min | Function::Max => (1, usize::MAX),
+            Function::Abs | Function::Floor | Function::Ceil => (1, 1),
+            Function::Round => (1, 2),
+        }
+    }
+}
+
+impl PointsFormula {
+    // Parse a formula, explaining what is wrong with it if it cannot be used.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+        };
+
+        if parser.tokens.is_empty() {
+            return Err(String::from("The formula is empty"));
+        }
+
+        let expression = parser.expression()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected \"{}\" in the formula", token));
+        }
+
+        Ok(Self {
+            source: source.trim().to_string(),
+            expression,
+        })
+    }
+
+    // The points for a one based rank in an event with the given number of participants.
+    pub fn evaluate(&self, rank: u64, participants: u64) -> Result<Decimal, String> {
+        evaluate(&self.expression, Decimal::from(rank), Decimal::from(participants))
+            .map_err(|err| format!("Formula \"{}\" failed for rank {} of {}: {}", self.source, rank, participants, err))
+    }
+}
+
+fn evaluate(expression: &Expression, rank: Decimal, participants: Decimal) -> Result<Decimal, String> {
+    let evaluate_all = |arguments: &Vec<Expression>| {
+        arguments.iter().map(|argument| evaluate(argument, rank, participants)).collect::<Result<Vec<Decimal>, String>>()
+    };
+
+    match expression {
+        Expression::Number(number) => Ok(*number),
+        Expression::Rank => Ok(rank),
+        Expression::Participants => Ok(participants),
+        Expression::Negate(operand) => Ok(-evaluate(operand, rank, participants)?),
+        Expression::Binary(operator, left, right) => {
+            let left = evaluate(left, rank, participants)?;
+            let right = evaluate(right, rank, participants)?;
+
+            let result = match operator {
+                Operator::Add => left.checked_add(right),
+                Operator::Subtract => left.checked_sub(right),
+                Operator::Multiply => left.checked_mul(right),
+                Operator::Divide => {
+                    if right.is_zero() {
+                        return Err(String::from("division by zero"));
+                    }
+                    left.checked_div(right)
+                }
+                Operator::Power => power(left, right)?,
+            };
+
+            result.ok_or_else(|| String::from("the result is too large"))
+        }
+        Expression::Function(function, arguments) => {
+            let arguments = evaluate_all(arguments)?;
+
+            Ok(match function {
+                Function::Min => arguments.into_iter().min().unwrap_or_default(),
+                Function::Max => arguments.into_iter().max().unwrap_or_default(),
+                Function::Abs => arguments[0].abs(),
+                Function::Floor => arguments[0].floor(),
+                Function::Ceil => arguments[0].ceil(),
+                Function::Round => {
+                    let decimal_places = match arguments.get(1) {
+                        // Normalize first so that 2.0 is read as 2 rather than a mantissa of 20.
+                        Some(decimal_places) if decimal_places.is_integer() && !decimal_places.is_sign_negative() => {
+                            u32::try_from(decimal_places.normalize().mantissa()).unwrap_or(u32::MAX)
+                        }
+                        Some(_) => return Err(String::from("round takes a whole number of decimal places")),
+                        None => 0,
+                    };
+                    arguments[0].round_dp_with_strategy(decimal_places, RoundingStrategy::MidpointAwayFromZero)
+                }
+            })
+        }
+    }
+}
+
+// Raise a number to a whole power by repeated squaring, so even a huge exponent takes only a
+// few steps. None as soon as the result is too large.
+fn power(base: Decimal, exponent: Decimal) -> Result<Option<Decimal>, String> {
+    if !exponent.is_integer() {
+        return Err(String::from("powers must be whole numbers"));
+    }
+
+    let exponent = exponent.normalize();
+
+    // A negative power is the positive power of the reciprocal.
+    let mut square = if exponent.is_sign_negative() {
+        if base.is_zero() {
+            return Err(String::from("division by zero"));
+        }
+        match Decimal::ONE.checked_div(base) {
+            Some(reciprocal) => reciprocal,
+            None => return Ok(None),
+        }
+    } else {
+        base
+    };
+
+    let mut remaining = exponent.mantissa().unsigned_abs();
+    let mut result = Decimal::ONE;
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            result = match result.checked_mul(square) {
+                Some(result) => result,
+                None => return Ok(None),
+            };
+        }
+
+        remaining >>= 1;
+        if remaining > 0 {
+            square = match square.checked_mul(square) {
+                Some(square) => square,
+                None => return Ok(None),
+            };
+        }
+    }
+
+    Ok(Some(result))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(Decimal),
+    Identifier(String),
+    Symbol(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Identifier(identifier) => write!(f, "{}", identifier),
+            Token::Symbol(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let characters = source.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < characters.len() {
+        let c = characters[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < characters.len() && (characters[i].is_ascii_digit() || characters[i] == '.') {
+                i += 1;
+            }
+            let number = characters[start..i].iter().collect::<String>();
+            match number.parse::<Decimal>() {
+                Ok(number) => tokens.push(Token::Number(number)),
+                Err(_) => return Err(format!("\"{}\" is not a number", number)),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < characters.len() && (characters[i].is_alphanumeric() || characters[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Identifier(characters[start..i].iter().collect::<String>().to_lowercase()));
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        } else {
+            return Err(format!("Unexpected \"{}\" in the formula", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_is(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(c)) if c == symbol => Ok(()),
+            Some(token) => Err(format!("Expected \"{}\" but found \"{}\"", symbol, token)),
+            None => Err(format!("Expected \"{}\" at the end of the formula", symbol)),
+        }
+    }
+
+    // expression = term (("+" | "-") term)*
+    fn expression(&mut self) -> Result<Expression, String> {
+        let mut left = self.term()?;
+
+        loop {
+            let operator = if self.next_is('+') {
+                Operator::Add
+            } else if self.next_is('-') {
+                Operator::Subtract
+            } else {
+                return Ok(left);
+            };
+
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.term()?));
+        }
+    }
+
+    // term = unary (("*" | "/") unary)*
+    fn term(&mut self) -> Result<Expression, String> {
+        let mut left = self.unary()?;
+
+        loop {
+            let operator = if self.next_is('*') {
+                Operator::Multiply
+            } else if self.next_is('/') {
+                Operator::Divide
+            } else {
+                return Ok(left);
+            };
+
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    // unary = "-" unary | power
+    fn unary(&mut self) -> Result<Expression, String> {
+        if self.next_is('-') {
+            return Ok(Expression::Negate(Box::new(self.unary()?)));
+        }
+
+        self.power()
+    }
+
+    // power = primary ("^" unary)?
+    fn power(&mut self) -> Result<Expression, String> {
+        let base = self.primary()?;
+
+        if self.next_is('^') {
+            return Ok(Expression::Binary(Operator::Power, Box::new(base), Box::new(self.unary()?)));
+        }
+
+        Ok(base)
+    }
+
+    // primary = number | "rank" | "participants" | function "(" arguments ")" | "(" expression ")"
+    fn primary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Expression::Number(number)),
+            Some(Token::Symbol('(')) => {
+                let expression = self.expression()?;
+                self.expect(')')?;
+                Ok(expression)
+            }
+            Some(Token::Identifier(identifier)) => match identifier.as_str() {
+                "rank" => Ok(Expression::Rank),
+                "participants" => Ok(Expression::Participants),
+                name => match Function::from_name(name) {
+                    Some(function) => self.function(function, name),
+                    None => Err(format!("Unknown name \"{}\", use rank, participants or a function such as max", name)),
+                },
+            },
+            Some(token) => Err(format!("Unexpected \"{}\" in the formula", token)),
+            None => Err(String::from("The formula ends too early")),
+        }
+    }
+
+    fn function(&mut self, function: Function, name: &str) -> Result<Expression, String> {
+        self.expect('(')?;
+
+        let mut arguments = vec![self.expression()?];
+        while self.next_is(',') {
+            arguments.push(self.expression()?);
+        }
+        self.expect(')')?;
+
+        let (fewest, most) = function.arity();
+        if arguments.len() < fewest || arguments.len() > most {
+            return Err(format!("{} does not take {} argument(s)", name, arguments.len()));
+        }
+
+        Ok(Expression::Function(function, arguments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use rust_decimal::Decimal;
+    use super::PointsFormula;
+
+    fn evaluate(source: &str) -> Result<Decimal, String> {
+        PointsFormula::parse(source)?.evaluate(1, 5)
+    }
+
+    fn decimal(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn negation_applies_after_powers() {
+        assert_eq!(evaluate("-2^2"), Ok(decimal("-4")));
+        assert_eq!(evaluate("(-2)^2"), Ok(decimal("4")));
+    }
+
+    #[test]
+    fn powers_are_right_associative() {
+        assert_eq!(evaluate("2^3^2"), Ok(decimal("512")));
+        assert_eq!(evaluate("2^-2"), Ok(decimal("0.25")));
+    }
+
+    #[test]
+    fn multiplication_comes_before_addition() {
+        assert_eq!(evaluate("1 + 2 * 3"), Ok(decimal("7")));
+        assert_eq!(evaluate("(1 + 2) * 3"), Ok(decimal("9")));
+        assert_eq!(evaluate("participants - rank + 1"), Ok(decimal("5")));
+    }
+
+    #[test]
+    fn huge_powers_finish_quickly() {
+        assert_eq!(evaluate("1^99999999999"), Ok(decimal("1")));
+        assert_eq!(evaluate("0.5^99999999999"), Ok(Decimal::ZERO));
+        assert_eq!(evaluate("2^99999999999"), Err(String::from("Formula \"2^99999999999\" failed for rank 1 of 5: the result is too large")));
+        assert_eq!(evaluate("2^-99999999999"), Ok(Decimal::ZERO));
+    }
+
+    #[test]
+    fn round_reads_decimal_places_written_with_a_fraction() {
+        assert_eq!(evaluate("round(1.23456, 2.0)"), Ok(decimal("1.23")));
+        assert_eq!(evaluate("round(1.23456, 2)"), Ok(decimal("1.23")));
+        assert_eq!(evaluate("round(2.5)"), Ok(decimal("3")));
+    }
+
+    #[test]
+    fn errors_explain_what_is_wrong() {
+        assert_eq!(PointsFormula::parse(" "), Err(String::from("The formula is empty")));
+        assert_eq!(PointsFormula::parse("2 +"), Err(String::from("The formula ends too early")));
+        assert_eq!(PointsFormula::parse("2 $ 3"), Err(String::from("Unexpected \"$\" in the formula")));
+        assert_eq!(PointsFormula::parse("(2 + 3"), Err(String::from("Expected \")\" at the end of the formula")));
+        assert_eq!(PointsFormula::parse("1.2.3"), Err(String::from("\"1.2.3\" is not a number")));
+        assert_eq!(PointsFormula::parse("place + 1"), Err(String::from("Unknown name \"place\", use rank, participants or a function such as max")));
+        assert_eq!(evaluate("1 / (rank - 1)"), Err(String::from("Formula \"1 / (rank - 1)\" failed for rank 1 of 5: division by zero")));
+        assert_eq!(evaluate("2^0.5"), Err(String::from("Formula \"2^0.5\" failed for rank 1 of 5: powers must be whole numbers")));
+        assert_eq!(evaluate("0^-1"), Err(String::from("Formula \"0^-1\" failed for rank 1 of 5: division by zero")));
+        assert_eq!(evaluate("round(1.5, -1)"), Err(String::from("Formula \"round(1.5, -1)\" failed for rank 1 of 5: round takes a whole number of decimal places")));
+    }
+}
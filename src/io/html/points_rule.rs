@@ -0,0 +1,105 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::discipline::{discipline_of, words, Discipline, DisciplineMapping};
+use crate::io::html::event::matches_pattern;
+use crate::io::html::result_set::ResultSet;
+use crate::io::html::scoring_system::ScoringSystem;
+
+// Points for a group of events, chosen by any combination of event name pattern, level,
+// discipline and scoring system. Criteria that are left empty match every event.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PointsRule {
+    // Wildcard pattern for the event name, e.g. "Senior*".
+    pub event_pattern: String,
+    // Level as it is written in event names, e.g. "Pre-Preliminary" or "Adult Bronze".
+    pub level: String,
+    pub discipline: Option<Discipline>,
+    pub scoring_system: Option<ScoringSystem>,
+    // Points for each placement, used when no sheet or formula is given.
+    pub points: Vec<Decimal>,
+    // A sheet of the scoring system spreadsheet to read a field size chart from instead.
+    pub sheet: Option<String>,
+    // A points formula of rank and participants to use instead, e.g. "participants - rank + 1".
+    pub formula: Option<String>,
+}
+
+impl Default for PointsRule {
+    fn default() -> Self {
+        Self {
+            event_pattern: String::new(),
+            level: String::new(),
+            discipline: None,
+            scoring_system: None,
+            points: vec![Decimal::from(3), Decimal::from(2), Decimal::from(1)],
+            sheet: None,
+            formula: None,
+        }
+    }
+}
+
+impl PointsRule {
+    pub fn matches(&self, result_set: &ResultSet, discipline_mappings: &[DisciplineMapping]) -> bool {
+        let event_name = result_set.event();
+
+        let pattern_matches = self.event_pattern.trim().is_empty() || matches_pattern(self.event_pattern.trim(), &event_name);
+        let level_matches = self.level.trim().is_empty() || contains_words(&event_name, &self.level);
+        let discipline_matches = self.discipline.is_none_or(|discipline| discipline_of(&event_name, discipline_mappings) == discipline);
+        let scoring_system_matches = self.scoring_system.as_ref().is_none_or(|scoring_system| *scoring_system == result_set.scoring_system);
+
+        pattern_matches && level_matches && discipline_matches && scoring_system_matches
+    }
+
+    // A short description of the events the rule applies to.
+    pub fn describe(&self) -> String {
+        let mut criteria = vec![];
+        if !self.event_pattern.trim().is_empty() {
+            criteria.push(format!("\"{}\"", self.event_pattern.trim()));
+        }
+        if !self.level.trim().is_empty() {
+            criteria.push(self.level.trim().to_string());
+        }
+        if let Some(discipline) = self.discipline {
+            criteria.push(discipline.get_name());
+        }
+        if let Some(scoring_system) = &self.scoring_system {
+            criteria.push(scoring_system.get_name());
+        }
+
+        if criteria.is_empty() {
+            String::from("All events")
+        } else {
+            criteria.join(", ")
+        }
+    }
+}
+
+// Whether the words of the phrase appear one after another in the text.
+fn contains_words(text: &str, phrase: &str) -> bool {
+    let text = words(text);
+    let phrase = words(phrase);
+
+    !phrase.is_empty() && text.windows(phrase.len()).any(|window| window == phrase.as_slice())
+}
@@ -1,17 +1,449 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
 use std::collections::HashMap;
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
 use crate::io::excel::scoring_system_reader;
+use crate::io::html::club_points::is_small_field;
+use crate::io::html::discipline::DisciplineMapping;
+use crate::io::html::points_formula::PointsFormula;
+use crate::io::html::points_rule::PointsRule;
+use crate::io::html::result_set::ResultSet;
+use crate::io::html::tie_policy::tied_with;
 use crate::settings::settings::Settings;
 
-pub struct PointsSystem {
-    single_points_system: Vec<f64>,
-    spreadsheet_points_system: HashMap<u64, Vec<f64>>,
+// Decides how many points each placement of an event is worth.
+pub trait PointsSystem {
+    // The points for each placement of the event a result is from, first place first. Places past
+    // the end of the table are worth nothing. Returns the reason if the event cannot be scored.
+    fn points_table(&self, result_set: &ResultSet) -> Result<Vec<Decimal>, String>;
+
+    // Explains how an event was scored when its own points could not be used, such as a chart
+    // column standing in for a field size the chart does not list.
+    fn substitution_note(&self, _result_set: &ResultSet) -> Option<String> {
+        None
+    }
+}
+
+// Which points system a competition is scored with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PointsSystemKind {
+    // The same points table for every event.
+    #[default]
+    FlatTable,
+    // A points table for each number of participants, read from a spreadsheet.
+    FieldSizeChart,
+    // A points table or chart for each group of events, e.g. one for each level.
+    EventRules,
+    // Points worked out from each skater's rank and the number of participants by a formula.
+    Formula,
+}
+
+impl PointsSystemKind {
+    pub fn get_name(&self) -> String {
+        match self {
+            PointsSystemKind::FlatTable => String::from("Flat Table"),
+            PointsSystemKind::FieldSizeChart => String::from("Field Size Chart"),
+            PointsSystemKind::EventRules => String::from("Event Rules"),
+            PointsSystemKind::Formula => String::from("Formula"),
+        }
+    }
+}
+
+// The same points for each placement in every event.
+pub struct FlatTable {
+    points: Vec<Decimal>,
+}
+
+impl FlatTable {
+    pub fn new(points: Vec<Decimal>) -> Self {
+        Self {
+            points,
+        }
+    }
+}
+
+impl PointsSystem for FlatTable {
+    fn points_table(&self, _result_set: &ResultSet) -> Result<Vec<Decimal>, String> {
+        Ok(self.points.clone())
+    }
+}
+
+// What to do when a points chart has no column for the number of participants in an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChartResolution {
+    // Use the column for the nearest smaller field, or the smallest column if every column is
+    // for a larger field.
+    #[default]
+    NearestSmaller,
+    // Use the column for the largest field in the chart.
+    Largest,
+    // Work the points out from the two nearest columns, following their trend past the ends of
+    // the chart.
+    Extrapolate,
+    // Stop tabulating until the chart has a column for every field size.
+    Refuse,
+}
+
+impl ChartResolution {
+    pub fn get_name(&self) -> String {
+        match self {
+            ChartResolution::NearestSmaller => String::from("Nearest Smaller"),
+            ChartResolution::Largest => String::from("Largest"),
+            ChartResolution::Extrapolate => String::from("Extrapolate"),
+            ChartResolution::Refuse => String::from("Refuse"),
+        }
+    }
+}
+
+// Points that depend on the number of participants in the event, one column of the
+// "Points Chart" spreadsheet for each number of participants.
+pub struct FieldSizeChart {
+    columns: HashMap<u64, Vec<Decimal>>,
+    resolution: ChartResolution,
+}
+
+impl FieldSizeChart {
+    pub fn new(columns: HashMap<u64, Vec<Decimal>>, resolution: ChartResolution) -> Self {
+        Self {
+            columns,
+            resolution,
+        }
+    }
+
+    pub fn read(path: Option<String>, resolution: ChartResolution) -> Result<Self, String> {
+        Ok(Self::new(scoring_system_reader::read_scoring_system_spreadsheet(path)?, resolution))
+    }
+
+    pub fn read_sheet(path: Option<String>, sheet: &str, resolution: ChartResolution) -> Result<Self, String> {
+        Ok(Self::new(scoring_system_reader::read_scoring_system_sheet(path, sheet)?, resolution))
+    }
+
+    // The points table for an event along with a note if it is not the event's own column.
+    fn resolve(&self, result_set: &ResultSet) -> Result<(Vec<Decimal>, Option<String>), String> {
+        let participants = match result_set.participants {
+            Some(participants) => participants,
+            None => return Err(format!("Failed to get number of participants at event {}", result_set.event())),
+        };
+
+        if let Some(column) = self.columns.get(&participants) {
+            return Ok((column.clone(), None));
+        }
+
+        let mut field_sizes = self.columns.keys().copied().collect::<Vec<u64>>();
+        field_sizes.sort();
+
+        let (smallest, largest) = match (field_sizes.first(), field_sizes.last()) {
+            (Some(smallest), Some(largest)) => (*smallest, *largest),
+            _ => return Err(format!("The points chart has no columns to score event {}", result_set.event())),
+        };
+
+        let missing = format!("No chart column for {} participants", participants);
+
+        match self.resolution {
+            ChartResolution::NearestSmaller => {
+                let field_size = field_sizes.iter().rev().find(|field_size| **field_size < participants).copied().unwrap_or(smallest);
+                Ok((self.columns[&field_size].clone(), Some(format!("{}, used the column for {}", missing, field_size))))
+            }
+            ChartResolution::Largest => {
+                Ok((self.columns[&largest].clone(), Some(format!("{}, used the column for {}", missing, largest))))
+            }
+            ChartResolution::Extrapolate => {
+                let below = field_sizes.iter().rev().find(|field_size| **field_size < participants).copied();
+                let above = field_sizes.iter().find(|field_size| **field_size > participants).copied();
+
+                // Interpolate between the columns either side, or continue from the two nearest
+                // columns past the end of the chart.
+                let (low, high) = match (below, above) {
+                    (Some(below), Some(above)) => (below, above),
+                    (Some(below), None) => (field_sizes.iter().rev().nth(1).copied().unwrap_or(below), below),
+                    (None, Some(above)) => (above, field_sizes.get(1).copied().unwrap_or(above)),
+                    (None, None) => (smallest, largest),
+                };
+
+                if low == high {
+                    return Ok((self.columns[&low].clone(), Some(format!("{}, used the column for {}", missing, low))));
+                }
+
+                let low_column = &self.columns[&low];
+                let high_column = &self.columns[&high];
+                let place_points = |column: &Vec<Decimal>, place: usize| column.get(place).copied().unwrap_or(Decimal::ZERO);
+                let fraction = (Decimal::from(participants) - Decimal::from(low)) / (Decimal::from(high) - Decimal::from(low));
+
+                let places = (participants as usize).max(low_column.len()).max(high_column.len());
+                let column = (0..places)
+                    .map(|place| {
+                        let low_points = place_points(low_column, place);
+                        let points = low_points + (place_points(high_column, place) - low_points) * fraction;
+                        points.max(Decimal::ZERO)
+                    })
+                    .collect();
+
+                Ok((column, Some(format!("{}, points worked out from the columns for {} and {}", missing, low, high))))
+            }
+            ChartResolution::Refuse => Err(format!(
+                "{} at event {}. Add the column to the points chart or choose how missing columns are resolved in the settings",
+                missing,
+                result_set.event(),
+            )),
+        }
+    }
+}
+
+impl PointsSystem for FieldSizeChart {
+    fn points_table(&self, result_set: &ResultSet) -> Result<Vec<Decimal>, String> {
+        self.resolve(result_set).map(|(column, _)| column)
+    }
+
+    fn substitution_note(&self, result_set: &ResultSet) -> Option<String> {
+        self.resolve(result_set).ok().and_then(|(_, note)| note)
+    }
 }
 
-impl PointsSystem {
-    pub fn default(settings: &Settings) -> Self {
+// Points from an expression of rank and participants, e.g. "max(0, participants - rank + 1)".
+pub struct Formula {
+    formula: PointsFormula,
+}
+
+impl Formula {
+    pub fn new(formula: PointsFormula) -> Self {
         Self {
-            single_points_system: settings.default_points_system.clone(),
-            spreadsheet_points_system: scoring_system_reader::read_scoring_system_spreadsheet(settings.scoring_system_file_name.clone()).unwrap(),
+            formula,
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        match PointsFormula::parse(source) {
+            Ok(formula) => Ok(Self::new(formula)),
+            Err(err) => Err(format!("Invalid points formula \"{}\": {}", source, err)),
+        }
+    }
+}
+
+impl PointsSystem for Formula {
+    fn points_table(&self, result_set: &ResultSet) -> Result<Vec<Decimal>, String> {
+        let participants = match result_set.participants {
+            Some(participants) => participants,
+            None => return Err(format!("Failed to get number of participants at event {}", result_set.event())),
+        };
+
+        // Cover every place in the field, and the skater's own place should fewer entries be
+        // counted toward the field size than were placed.
+        let places = participants.max(result_set.rank.unwrap_or(0));
+
+        (1..=places)
+            .map(|place| self.formula.evaluate(place, participants).map_err(|err| format!("{} at event {}", err, result_set.event())))
+            .collect()
+    }
+}
+
+// The points system of the first rule matching an event, or the fallback for events that no
+// rule matches.
+pub struct EventRules {
+    rules: Vec<(PointsRule, Box<dyn PointsSystem>)>,
+    discipline_mappings: Vec<DisciplineMapping>,
+    fallback: Box<dyn PointsSystem>,
+}
+
+impl EventRules {
+    // Build the points system of every rule, reading the sheets they name from the scoring
+    // system spreadsheet. A sheet takes precedence over a formula, and a formula over points.
+    pub fn new(rules: &[PointsRule], discipline_mappings: &[DisciplineMapping], spreadsheet: Option<String>, resolution: ChartResolution, fallback: Box<dyn PointsSystem>) -> Result<Self, String> {
+        let mut rule_systems: Vec<(PointsRule, Box<dyn PointsSystem>)> = vec![];
+
+        for rule in rules {
+            let sheet = rule.sheet.as_deref().map(str::trim).filter(|sheet| !sheet.is_empty());
+            let formula = rule.formula.as_deref().map(str::trim).filter(|formula| !formula.is_empty());
+
+            let points_system: Result<Box<dyn PointsSystem>, String> = match (sheet, formula) {
+                (Some(sheet), _) => FieldSizeChart::read_sheet(spreadsheet.clone(), sheet, resolution).map(|chart| Box::new(chart) as Box<dyn PointsSystem>),
+                (None, Some(formula)) => Formula::parse(formula).map(|formula| Box::new(formula) as Box<dyn PointsSystem>),
+                (None, None) => Ok(Box::new(FlatTable::new(rule.points.clone()))),
+            };
+
+            let points_system = match points_system {
+                Ok(points_system) => points_system,
+                Err(err) => return Err(format!("Points rule for {}: {}", rule.describe(), err)),
+            };
+
+            rule_systems.push((rule.clone(), points_system));
+        }
+
+        Ok(Self {
+            rules: rule_systems,
+            discipline_mappings: discipline_mappings.to_vec(),
+            fallback,
+        })
+    }
+}
+
+impl EventRules {
+    fn points_system_for(&self, result_set: &ResultSet) -> &dyn PointsSystem {
+        match self.rules.iter().find(|(rule, _)| rule.matches(result_set, &self.discipline_mappings)) {
+            Some((_, points_system)) => points_system.as_ref(),
+            None => self.fallback.as_ref(),
+        }
+    }
+}
+
+impl PointsSystem for EventRules {
+    fn points_table(&self, result_set: &ResultSet) -> Result<Vec<Decimal>, String> {
+        self.points_system_for(result_set).points_table(result_set)
+    }
+
+    fn substitution_note(&self, result_set: &ResultSet) -> Option<String> {
+        self.points_system_for(result_set).substitution_note(result_set)
+    }
+}
+
+// Create the points system chosen in the settings.
+pub fn from_settings(settings: &Settings) -> Result<Box<dyn PointsSystem>, String> {
+    Ok(match settings.points_system {
+        PointsSystemKind::FlatTable => Box::new(FlatTable::new(settings.default_points_system.clone())),
+        PointsSystemKind::FieldSizeChart => Box::new(FieldSizeChart::read(settings.scoring_system_file_name.clone(), settings.chart_resolution)?),
+        PointsSystemKind::EventRules => Box::new(EventRules::new(
+            &settings.points_rules,
+            &settings.discipline_mappings,
+            settings.scoring_system_file_name.clone(),
+            settings.chart_resolution,
+            Box::new(FlatTable::new(settings.default_points_system.clone())),
+        )?),
+        PointsSystemKind::Formula => Box::new(Formula::parse(&settings.points_formula)?),
+    })
+}
+
+// The points a result earns before they are credited to its clubs, taking ties and the minimum
+// field size into account. Skaters who were not placed earn nothing.
+pub fn result_points(points_system: &dyn PointsSystem, result_set: &ResultSet, ties: &HashMap<(String, u64), u64>, settings: &Settings) -> Result<Decimal, String> {
+    if !result_set.is_placed() {
+        return Ok(Decimal::ZERO);
+    }
+
+    let rank = match result_set.rank {
+        Some(rank) if rank > 0 => (rank - 1) as usize,
+        _ => return Err(format!("Failed to get rank at event {}, skater {}", result_set.event(), result_set.name())),
+    };
+
+    let points_table = points_system.points_table(result_set)?;
+    let mut points = settings.tie_policy.points(&points_table, rank, tied_with(ties, result_set));
+
+    if is_small_field(result_set, settings) {
+        points *= settings.small_field_points_multiplier;
+    }
+
+    Ok(points)
+}
+
+// The names of every event scored with a substitute for its own points, in the order they appear.
+pub fn substituted_events(points_system: &dyn PointsSystem, result_sets: &[ResultSet]) -> Vec<String> {
+    let mut events: Vec<String> = vec![];
+    for result_set in result_sets {
+        if result_set.is_placed() && !events.contains(&result_set.event()) && points_system.substitution_note(result_set).is_some() {
+            events.push(result_set.event());
         }
     }
-}
\ No newline at end of file
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use rust_decimal::Decimal;
+    use crate::io::html::result_set::ResultSet;
+    use crate::io::html::scoring_system::ScoringSystem;
+    use super::{ChartResolution, FieldSizeChart, PointsSystem};
+
+    fn decimals(values: &[&str]) -> Vec<Decimal> {
+        values.iter().map(|value| Decimal::from_str(value).unwrap()).collect()
+    }
+
+    // A chart with columns for fields of 2 and 4 skaters.
+    fn chart(resolution: ChartResolution) -> FieldSizeChart {
+        FieldSizeChart::new(HashMap::from([(2, decimals(&["4", "2"])), (4, decimals(&["8", "4", "2", "1"]))]), resolution)
+    }
+
+    fn event(participants: u64) -> ResultSet {
+        let mut result_set = ResultSet::new(ScoringSystem::IJS);
+        result_set.event = Some(String::from("Juvenile Girls"));
+        result_set.participants = Some(participants);
+        result_set
+    }
+
+    #[test]
+    fn own_column_is_used_whatever_the_resolution() {
+        let chart = chart(ChartResolution::Refuse);
+
+        assert_eq!(chart.points_table(&event(4)), Ok(decimals(&["8", "4", "2", "1"])));
+        assert_eq!(chart.substitution_note(&event(4)), None);
+    }
+
+    #[test]
+    fn nearest_smaller_uses_the_column_below_or_the_smallest() {
+        let chart = chart(ChartResolution::NearestSmaller);
+
+        assert_eq!(chart.points_table(&event(3)), Ok(decimals(&["4", "2"])));
+        assert_eq!(chart.points_table(&event(6)), Ok(decimals(&["8", "4", "2", "1"])));
+        assert_eq!(chart.points_table(&event(1)), Ok(decimals(&["4", "2"])));
+        assert_eq!(chart.substitution_note(&event(3)), Some(String::from("No chart column for 3 participants, used the column for 2")));
+    }
+
+    #[test]
+    fn largest_uses_the_largest_column() {
+        let chart = chart(ChartResolution::Largest);
+
+        assert_eq!(chart.points_table(&event(1)), Ok(decimals(&["8", "4", "2", "1"])));
+        assert_eq!(chart.points_table(&event(3)), Ok(decimals(&["8", "4", "2", "1"])));
+    }
+
+    #[test]
+    fn extrapolate_interpolates_between_and_continues_past_the_columns() {
+        let chart = chart(ChartResolution::Extrapolate);
+
+        assert_eq!(chart.points_table(&event(3)), Ok(decimals(&["6", "3", "1", "0.5"])));
+        assert_eq!(chart.points_table(&event(5)), Ok(decimals(&["10", "5", "3", "1.5", "0"])));
+    }
+
+    #[test]
+    fn extrapolate_awards_no_negative_points() {
+        let chart = FieldSizeChart::new(HashMap::from([(2, decimals(&["4", "4"])), (3, decimals(&["4", "1", "0"]))]), ChartResolution::Extrapolate);
+
+        assert_eq!(chart.points_table(&event(5)), Ok(decimals(&["4", "0", "0", "0", "0"])));
+    }
+
+    #[test]
+    fn refuse_fails_without_the_column() {
+        let chart = chart(ChartResolution::Refuse);
+
+        assert_eq!(
+            chart.points_table(&event(3)),
+            Err(String::from("No chart column for 3 participants at event Juvenile Girls. Add the column to the points chart or choose how missing columns are resolved in the settings")),
+        );
+    }
+
+    #[test]
+    fn missing_column_is_resolved_to_the_nearest_smaller_by_default() {
+        assert_eq!(ChartResolution::default(), ChartResolution::NearestSmaller);
+    }
+}
@@ -1,7 +1,21 @@
+use std::collections::HashMap;
+use crate::io::html::competitor_status::CompetitorStatus;
 use crate::io::html::scoring_system::ScoringSystem;
+use crate::io::html::team_entry::TeamMember;
+use crate::settings::club_aliases::ClubAliases;
 
-pub fn clean_club_names(result_sets: &mut Vec<ResultSet>) {
+pub fn clean_club_names(result_sets: &mut Vec<ResultSet>, club_aliases: &ClubAliases) {
     for result_set in result_sets {
+        for member in &mut result_set.members {
+            for character_entities in crate::io::html::parser::HTML_CHARACTER_ENTITIES {
+                member.club = member.club.replace(character_entities.0, character_entities.1);
+            }
+
+            if let Some(club) = club_aliases.canonical(&member.club) {
+                member.club = club.clone();
+            }
+        }
+
         let name = match &result_set.club {
             Some(name) => { name }
             None => { continue }
@@ -12,10 +26,29 @@ pub fn clean_club_names(result_sets: &mut Vec<ResultSet>) {
             temp = temp.replace(character_entities.0, character_entities.1);
         }
 
+        if let Some(club) = club_aliases.canonical(&temp) {
+            temp = club.clone();
+        }
+
         result_set.club = Some(temp);
     }
 }
 
+// Set the field size of every result to the number of entries in its event whose status is
+// counted, e.g. only those who placed, or also those who withdrew.
+pub fn count_participants(result_sets: &mut [ResultSet], counted_statuses: &[CompetitorStatus]) {
+    let mut field_sizes: HashMap<String, u64> = HashMap::new();
+    for result_set in result_sets.iter() {
+        let field_size = field_sizes.entry(result_set.event()).or_insert(0);
+        if counted_statuses.contains(&result_set.status) {
+            *field_size += 1;
+        }
+    }
+
+    for result_set in result_sets.iter_mut() {
+        result_set.participants = field_sizes.get(&result_set.event()).copied();
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct ResultSet {
@@ -25,6 +58,11 @@ pub struct ResultSet {
     pub(crate) rank: Option<u64>,
     pub(crate) participants: Option<u64>,
     pub(crate) scoring_system: ScoringSystem,
+    // Every skater of a duet, pair or other team entry. Empty for single skaters.
+    pub(crate) members: Vec<TeamMember>,
+    pub(crate) status: CompetitorStatus,
+    // Skaters of the entry whose club the skater roster could not settle.
+    pub(crate) unmatched_skaters: Vec<String>,
 }
 
 impl ResultSet {
@@ -36,6 +74,9 @@ impl ResultSet {
             participants: None,
             club: None,
             scoring_system,
+            members: vec![],
+            status: CompetitorStatus::Placed,
+            unmatched_skaters: vec![],
         }
     }
 
@@ -62,4 +103,41 @@ impl ResultSet {
     pub fn scoring_system(&self) -> &ScoringSystem {
         &self.scoring_system
     }
+
+    pub fn members(&self) -> &Vec<TeamMember> {
+        &self.members
+    }
+
+    pub fn unmatched_skaters(&self) -> &Vec<String> {
+        &self.unmatched_skaters
+    }
+
+    pub fn status(&self) -> CompetitorStatus {
+        self.status
+    }
+
+    // Whether the entry finished with a placement and can earn points.
+    pub fn is_placed(&self) -> bool {
+        self.status == CompetitorStatus::Placed
+    }
+
+    pub fn is_team_entry(&self) -> bool {
+        self.members.len() > 1
+    }
+
+    // Every club represented by the entry, in the order the skaters are listed.
+    pub fn clubs(&self) -> Vec<String> {
+        if self.members.is_empty() {
+            return vec![self.club.clone().unwrap_or_default()];
+        }
+
+        let mut clubs: Vec<String> = vec![];
+        for member in &self.members {
+            if !clubs.contains(&member.club) {
+                clubs.push(member.club.clone());
+            }
+        }
+
+        clubs
+    }
 }
\ No newline at end of file
@@ -20,41 +20,71 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-// Quick sort implementation.
-
+use std::cmp::Ordering;
+use serde_derive::{Deserialize, Serialize};
 use crate::io::html::club_points::ClubPoints;
 
-pub(crate) fn sort_results(club_points: &mut Vec<ClubPoints>) {
-    sort(club_points);
-    club_points.reverse();
+// A rule that separates clubs with the same total, applied in the order they are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreaker {
+    MostFirstPlaces,
+    MostSecondPlaces,
+    MostEntries,
+    FewestEntries,
+    Alphabetical,
 }
 
-fn sort(points: &mut Vec<ClubPoints>) {
-    let start = 0;
-    let end = points.len() - 1;
-    sort_partition(points, start, end as isize);
-}
+impl TieBreaker {
+    pub const ALL: [TieBreaker; 5] = [TieBreaker::MostFirstPlaces, TieBreaker::MostSecondPlaces, TieBreaker::MostEntries, TieBreaker::FewestEntries, TieBreaker::Alphabetical];
 
-fn sort_partition(points: &mut Vec<ClubPoints>, start: isize, end: isize) {
-    if start < end && end - start >= 1 {
-        let pivot = partition(points, start, end);
-        sort_partition(points, start, pivot - 1);
-        sort_partition(points, pivot + 1, end);
+    // Which of two clubs ranks higher by this rule, or Equal if the rule does not separate them.
+    pub fn compare(&self, a: &ClubPoints, b: &ClubPoints) -> Ordering {
+        match self {
+            TieBreaker::MostFirstPlaces => b.placements(1).cmp(&a.placements(1)),
+            TieBreaker::MostSecondPlaces => b.placements(2).cmp(&a.placements(2)),
+            TieBreaker::MostEntries => b.entries().cmp(&a.entries()),
+            TieBreaker::FewestEntries => a.entries().cmp(&b.entries()),
+            TieBreaker::Alphabetical => a.club().to_lowercase().cmp(&b.club().to_lowercase()),
+        }
     }
-}
 
-fn partition(points: &mut Vec<ClubPoints>, l: isize, h: isize) -> isize {
-    let pivot = points[h as usize].calc_total();
-    let mut i = l - 1;
-
-    for j in l..h {
-        if &points[j as usize].calc_total() <= &pivot {
-            i = i + 1;
-            points.swap(i as usize, j as usize);
+    pub fn get_name(&self) -> String {
+        match self {
+            TieBreaker::MostFirstPlaces => String::from("Most First Places"),
+            TieBreaker::MostSecondPlaces => String::from("Most Second Places"),
+            TieBreaker::MostEntries => String::from("Most Entries"),
+            TieBreaker::FewestEntries => String::from("Fewest Entries"),
+            TieBreaker::Alphabetical => String::from("Alphabetical"),
         }
     }
+}
 
-    points.swap((i + 1) as usize, h as usize);
+// Which of two clubs ranks higher by total and then by each tie-breaker.
+fn compare_standings(a: &ClubPoints, b: &ClubPoints, tie_breakers: &[TieBreaker]) -> Ordering {
+    tie_breakers
+        .iter()
+        .fold(b.calc_total().cmp(&a.calc_total()), |ordering, tie_breaker| ordering.then_with(|| tie_breaker.compare(a, b)))
+}
 
-    i + 1
-}
\ No newline at end of file
+// Sort the clubs from the highest total down. Clubs that are still tied are listed by name so
+// the order is the same every time, even though they share a rank.
+pub fn sort_results(club_points: &mut [ClubPoints], tie_breakers: &[TieBreaker]) {
+    club_points.sort_by(|a, b| compare_standings(a, b, tie_breakers).then_with(|| a.club().cmp(b.club())));
+}
+
+// The rank of each club in sorted standings. Clubs the tie-breakers cannot separate share a
+// rank, and the ranks after them are skipped, e.g. 1, 2, 2, 4.
+pub fn shared_ranks(club_points: &[ClubPoints], tie_breakers: &[TieBreaker]) -> Vec<u64> {
+    let mut ranks: Vec<u64> = vec![];
+
+    for (i, club) in club_points.iter().enumerate() {
+        let rank = match i {
+            0 => 1,
+            _ if compare_standings(&club_points[i - 1], club, tie_breakers) == Ordering::Equal => ranks[i - 1],
+            _ => i as u64 + 1,
+        };
+        ranks.push(rank);
+    }
+
+    ranks
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, PartialEq)]
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ScoringSystem {
     IJS,
     SixO,
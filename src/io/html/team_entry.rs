@@ -0,0 +1,120 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::result_set::ResultSet;
+
+// One skater of a duet, pair, dance couple or other entry with more than one skater.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TeamMember {
+    pub(crate) name: String,
+    pub(crate) club: String,
+}
+
+impl TeamMember {
+    pub fn new(name: String, club: String) -> Self {
+        Self {
+            name,
+            club,
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn club(&self) -> &String {
+        &self.club
+    }
+}
+
+// How an entry's points are credited when its skaters represent different clubs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CrossClubCredit {
+    // Every skater earns an equal share of the points for their own club.
+    #[default]
+    SplitEvenly,
+    // Every club represented in the entry receives the full points.
+    FullCreditToEach,
+    // The club of the first listed skater receives the full points.
+    FirstListedClub,
+}
+
+impl CrossClubCredit {
+    // The points each club receives for a result.
+    pub fn credits(&self, result_set: &ResultSet, points: Decimal) -> Vec<(String, Decimal)> {
+        let clubs = result_set.clubs();
+
+        if result_set.members.is_empty() || clubs.len() < 2 {
+            return vec![(result_set.club.clone().unwrap_or_default(), points)];
+        }
+
+        match self {
+            CrossClubCredit::SplitEvenly => {
+                let total_members = Decimal::from(result_set.members.len());
+                clubs.into_iter().map(|club| {
+                    let members = result_set.members.iter().filter(|member| member.club == club).count();
+                    (club, points * Decimal::from(members) / total_members)
+                }).collect()
+            }
+            CrossClubCredit::FullCreditToEach => {
+                clubs.into_iter().map(|club| (club, points)).collect()
+            }
+            CrossClubCredit::FirstListedClub => {
+                vec![(result_set.members[0].club.clone(), points)]
+            }
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            CrossClubCredit::SplitEvenly => String::from("Split Evenly"),
+            CrossClubCredit::FullCreditToEach => String::from("Full Credit to Each"),
+            CrossClubCredit::FirstListedClub => String::from("First Listed Club"),
+        }
+    }
+}
+
+// Combine the skaters of an entry into one name.
+pub fn combine_member_names(members: &[TeamMember]) -> String {
+    members.iter().map(|member| member.name.as_str()).collect::<Vec<&str>>().join(" / ")
+}
+
+// Skaters listed without a club share the club of the next skater that has one, or the previous
+// skater's when none follow, as in "A<br>B, Club".
+pub fn fill_missing_member_clubs(members: &mut [TeamMember]) {
+    for i in 0..members.len() {
+        if !members[i].club.is_empty() {
+            continue;
+        }
+
+        let club = members[i + 1..].iter()
+            .chain(members[..i].iter().rev())
+            .find(|member| !member.club.is_empty())
+            .map(|member| member.club.clone());
+
+        if let Some(club) = club {
+            members[i].club = club;
+        }
+    }
+}
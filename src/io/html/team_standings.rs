@@ -0,0 +1,196 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use iced::widget::shader::wgpu::naga::FastHashMap;
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::club_matcher::normalize_club_name;
+use crate::io::html::club_points::{sum_credits, truncated_club_merges, ClubPoints};
+use crate::io::html::event::matches_pattern;
+use crate::io::html::participation::participation_credits;
+use crate::io::html::points_system::PointsSystem;
+use crate::io::html::result_set::ResultSet;
+use crate::io::html::results_sorter::sort_results;
+use crate::settings::settings::Settings;
+
+// A team made up of several clubs, e.g. a region or section in a regional team competition.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamDefinition {
+    pub team: String,
+    pub clubs: Vec<String>, // Club names, which may use * and ? as wildcards, e.g. "*Boston*".
+}
+
+impl TeamDefinition {
+    pub fn includes(&self, club: &str) -> bool {
+        let normalized = normalize_club_name(club);
+        self.clubs
+            .iter()
+            .filter(|pattern| !pattern.trim().is_empty())
+            .any(|pattern| matches_pattern(pattern.trim(), club) || normalize_club_name(pattern) == normalized)
+    }
+}
+
+// Whether clubs are grouped into teams at all.
+pub fn has_teams(settings: &Settings) -> bool {
+    settings.group_teams_by_region || settings.team_definitions.iter().any(|definition| !definition.team.trim().is_empty())
+}
+
+// The team a club is credited to: the first team definition listing it, or else its region in
+// the club directory when teams are grouped by region.
+pub fn team_of(club_points: &ClubPoints, settings: &Settings) -> Option<String> {
+    settings.team_definitions
+        .iter()
+        .filter(|definition| !definition.team.trim().is_empty())
+        .find(|definition| definition.includes(club_points.club()))
+        .map(|definition| definition.team.trim().to_string())
+        .or_else(|| {
+            club_points.directory_club()
+                .and_then(|directory_club| directory_club.region())
+                .filter(|_| settings.group_teams_by_region)
+                .cloned()
+        })
+}
+
+// Team totals, sorted like the club standings. Each result is credited to the teams of its
+// clubs, so an entry whose clubs are on the same team counts once for that team however its
+// points are shared between the clubs. Empty when clubs are not grouped into teams.
+pub fn team_points(results_sets: &[ResultSet], club_points: &[ClubPoints], points_system: &dyn PointsSystem, settings: &Settings) -> Result<Vec<ClubPoints>, String> {
+    if !has_teams(settings) {
+        return Ok(vec![]);
+    }
+
+    let club_teams: FastHashMap<String, String> = club_points
+        .iter()
+        .filter_map(|club_points| Some((club_points.club().clone(), team_of(club_points, settings)?)))
+        .collect();
+
+    // A club name cut short in 6.0 results belongs to the team of the club it is merged into.
+    let truncated: FastHashMap<String, String> = if settings.attempt_automatic_60_club_name_recombination {
+        truncated_club_merges(&standings_clubs(results_sets, settings)).into_iter().collect()
+    } else {
+        FastHashMap::default()
+    };
+
+    let credited_to = |club: &str| {
+        let club = truncated.get(club).map(String::as_str).unwrap_or(club);
+        club_teams.get(club).cloned()
+    };
+
+    let mut teams: Vec<ClubPoints> = sum_credits(results_sets, points_system, settings, &credited_to)?.into_values().collect();
+    sort_results(&mut teams, &settings.standings_tie_breakers);
+
+    Ok(teams)
+}
+
+// Every club that takes part in the standings, before any cut short names are merged.
+fn standings_clubs(results_sets: &[ResultSet], settings: &Settings) -> Vec<String> {
+    let mut clubs: Vec<String> = vec![];
+    let placed = results_sets.iter().filter(|results_set| results_set.is_placed()).flat_map(|results_set| results_set.clubs());
+    let participating = participation_credits(results_sets, settings).into_iter().flatten().map(|(club, _)| club);
+
+    for club in placed.chain(participating) {
+        if !clubs.contains(&club) {
+            clubs.push(club);
+        }
+    }
+
+    clubs
+}
+
+// The clubs in the standings that are on no team, so their points count toward no team total.
+pub fn unassigned_clubs(club_points: &[ClubPoints], settings: &Settings) -> Vec<String> {
+    if !has_teams(settings) {
+        return vec![];
+    }
+
+    club_points
+        .iter()
+        .filter(|club_points| team_of(club_points, settings).is_none())
+        .map(|club_points| club_points.club().clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use crate::io::html::club_points::ClubPoints;
+    use crate::io::html::points_system::FlatTable;
+    use crate::io::html::result_set::ResultSet;
+    use crate::io::html::scoring_system::ScoringSystem;
+    use crate::io::html::team_entry::{CrossClubCredit, TeamMember};
+    use crate::settings::settings::Settings;
+    use super::{team_points, TeamDefinition};
+
+    fn pair(first: (&str, &str), second: (&str, &str), rank: u64) -> ResultSet {
+        let mut result_set = ResultSet::new(ScoringSystem::IJS);
+        result_set.event = Some(String::from("Junior Pairs"));
+        result_set.rank = Some(rank);
+        result_set.participants = Some(4);
+        result_set.members = vec![
+            TeamMember::new(first.0.to_string(), first.1.to_string()),
+            TeamMember::new(second.0.to_string(), second.1.to_string()),
+        ];
+        result_set.name = Some(format!("{} / {}", first.0, second.0));
+        result_set.club = Some(first.1.to_string());
+        result_set
+    }
+
+    fn settings(cross_club_credit: CrossClubCredit) -> Settings {
+        Settings {
+            cross_club_credit,
+            team_definitions: vec![TeamDefinition {
+                team: String::from("North"),
+                clubs: vec![String::from("Club A"), String::from("Club B")],
+            }],
+            ..Settings::default()
+        }
+    }
+
+    fn north_total(results_sets: &[ResultSet], settings: &Settings) -> Decimal {
+        let clubs = ["Club A", "Club B", "Club C"].map(|club| ClubPoints::new(club.to_string()));
+        let points_system = FlatTable::new(vec![Decimal::from(3), Decimal::from(2), Decimal::ONE]);
+        let teams = team_points(results_sets, &clubs, &points_system, settings).unwrap();
+
+        teams.iter().find(|team| team.club() == "North").unwrap().calc_total()
+    }
+
+    #[test]
+    fn pair_on_one_team_counts_once_with_full_credit_to_each_club() {
+        let results_sets = [pair(("Ann", "Club A"), ("Bob", "Club B"), 1)];
+
+        assert_eq!(north_total(&results_sets, &settings(CrossClubCredit::FullCreditToEach)), Decimal::from(3));
+    }
+
+    #[test]
+    fn pair_on_one_team_counts_once_when_split_between_clubs() {
+        let results_sets = [pair(("Ann", "Club A"), ("Bob", "Club B"), 1)];
+
+        assert_eq!(north_total(&results_sets, &settings(CrossClubCredit::SplitEvenly)), Decimal::from(3));
+    }
+
+    #[test]
+    fn pair_across_teams_credits_only_its_share_to_each_team() {
+        let results_sets = [pair(("Ann", "Club A"), ("Cal", "Club C"), 2)];
+
+        assert_eq!(north_total(&results_sets, &settings(CrossClubCredit::SplitEvenly)), Decimal::ONE);
+    }
+}
@@ -0,0 +1,142 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use crate::io::html::result_set::ResultSet;
+
+// How points are awarded when several skaters share a placement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TiePolicy {
+    // Each tied skater gets the average of the points for every place the tie occupies.
+    #[default]
+    Average,
+    // Each tied skater gets the points of the highest place the tie occupies.
+    Higher,
+    // Each tied skater gets the points of the lowest place the tie occupies.
+    Lower,
+}
+
+impl TiePolicy {
+    // Points for a zero based rank shared by the given number of skaters. Places past the end of
+    // the points table are worth nothing.
+    pub fn points(&self, points_table: &[Decimal], rank: usize, tied: u64) -> Decimal {
+        let tied = tied.max(1) as usize;
+        let place_points = |place: usize| points_table.get(place).copied().unwrap_or(Decimal::ZERO);
+
+        match self {
+            TiePolicy::Average => {
+                (rank..rank + tied).map(place_points).sum::<Decimal>() / Decimal::from(tied)
+            }
+            TiePolicy::Higher => place_points(rank),
+            TiePolicy::Lower => place_points(rank + tied - 1),
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            TiePolicy::Average => String::from("Average"),
+            TiePolicy::Higher => String::from("Higher"),
+            TiePolicy::Lower => String::from("Lower"),
+        }
+    }
+}
+
+// Count how many skaters hold each rank of each event.
+pub fn count_ties(result_sets: &[ResultSet]) -> HashMap<(String, u64), u64> {
+    let mut ties = HashMap::new();
+
+    for result_set in result_sets {
+        if let Some(rank) = result_set.rank {
+            *ties.entry((result_set.event(), rank)).or_insert(0) += 1;
+        }
+    }
+
+    ties
+}
+
+// The number of skaters sharing the rank of a result, including the skater themselves.
+pub fn tied_with(ties: &HashMap<(String, u64), u64>, result_set: &ResultSet) -> u64 {
+    match result_set.rank {
+        Some(rank) => *ties.get(&(result_set.event(), rank)).unwrap_or(&1),
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use crate::io::html::result_set::ResultSet;
+    use crate::io::html::scoring_system::ScoringSystem;
+    use super::{count_ties, tied_with, TiePolicy};
+
+    fn table() -> Vec<Decimal> {
+        [6, 4, 3, 2].map(Decimal::from).to_vec()
+    }
+
+    fn result(event: &str, rank: Option<u64>) -> ResultSet {
+        let mut result_set = ResultSet::new(ScoringSystem::IJS);
+        result_set.event = Some(event.to_string());
+        result_set.rank = rank;
+        result_set
+    }
+
+    #[test]
+    fn two_way_tie() {
+        // Two skaters tied for 2nd share 2nd and 3rd place.
+        assert_eq!(TiePolicy::Average.points(&table(), 1, 2), Decimal::new(35, 1));
+        assert_eq!(TiePolicy::Higher.points(&table(), 1, 2), Decimal::from(4));
+        assert_eq!(TiePolicy::Lower.points(&table(), 1, 2), Decimal::from(3));
+    }
+
+    #[test]
+    fn three_way_tie() {
+        // Three skaters tied for 1st share 1st to 3rd place.
+        assert_eq!(TiePolicy::Average.points(&table(), 0, 3), Decimal::from(13) / Decimal::from(3));
+        assert_eq!(TiePolicy::Higher.points(&table(), 0, 3), Decimal::from(6));
+        assert_eq!(TiePolicy::Lower.points(&table(), 0, 3), Decimal::from(3));
+    }
+
+    #[test]
+    fn tie_past_the_end_of_the_table_counts_those_places_as_nothing() {
+        assert_eq!(TiePolicy::Average.points(&table(), 3, 2), Decimal::ONE);
+        assert_eq!(TiePolicy::Lower.points(&table(), 3, 2), Decimal::ZERO);
+        assert_eq!(TiePolicy::Higher.points(&table(), 3, 0), Decimal::from(2));
+    }
+
+    #[test]
+    fn ties_are_counted_per_event_and_rank() {
+        let results_sets = [
+            result("Juvenile Girls", Some(2)),
+            result("Juvenile Girls", Some(2)),
+            result("Juvenile Girls", Some(1)),
+            result("Novice Ladies", Some(2)),
+            result("Novice Ladies", None),
+            result("Novice Ladies", None),
+        ];
+        let ties = count_ties(&results_sets);
+
+        assert_eq!(results_sets.iter().map(|result_set| tied_with(&ties, result_set)).collect::<Vec<u64>>(), vec![2, 2, 1, 1, 1, 1]);
+        assert_eq!(ties.len(), 3);
+    }
+}
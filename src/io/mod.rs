@@ -1,3 +1,7 @@
+pub mod club_directory;
 pub mod file_utils;
 pub mod excel;
-pub mod html;
\ No newline at end of file
+pub mod html;
+pub mod rounding;
+pub mod roster;
+pub mod table_file;
\ No newline at end of file
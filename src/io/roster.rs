@@ -0,0 +1,253 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::io::html::club_matcher::normalize_club_name;
+use crate::io::html::result_set::ResultSet;
+use crate::io::table_file::{cell, header_column, read_rows};
+use crate::settings::settings::Settings;
+
+// Header names recognized for each column of a roster, compared ignoring case.
+const NAME_HEADERS: [&str; 4] = ["name", "skater", "skater name", "full name"];
+const FIRST_NAME_HEADERS: [&str; 3] = ["first name", "first", "given name"];
+const LAST_NAME_HEADERS: [&str; 4] = ["last name", "last", "surname", "family name"];
+const CLUB_HEADERS: [&str; 3] = ["club", "club name", "home club"];
+
+// A skater entered in the competition and the club they represent.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RosterEntry {
+    name: String,
+    club: String,
+}
+
+impl RosterEntry {
+    pub fn new(name: String, club: String) -> Self {
+        Self {
+            name,
+            club,
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn club(&self) -> &String {
+        &self.club
+    }
+}
+
+// The entries of a competition, read from a CSV file or the first sheet of a spreadsheet. The
+// first row names the columns. Skaters are named in one column, or in first and last name columns.
+#[derive(Debug, Clone, Default)]
+pub struct Roster {
+    entries: Vec<RosterEntry>,
+}
+
+impl Roster {
+    pub fn new(entries: Vec<RosterEntry>) -> Self {
+        Self {
+            entries,
+        }
+    }
+
+    // The roster the settings point to, if any.
+    pub fn from_settings(settings: &Settings) -> Result<Option<Self>, String> {
+        match settings.roster_file_name.as_ref().filter(|path| !path.trim().is_empty()) {
+            Some(path) => Roster::read(path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn read(path: &str) -> Result<Self, String> {
+        let rows = read_rows(path, "skater roster")?;
+
+        Roster::from_rows(rows).map_err(|err| format!("Failed to read the skater roster {}: {}", path, err))
+    }
+
+    fn from_rows(rows: Vec<Vec<String>>) -> Result<Self, String> {
+        let mut rows = rows.into_iter();
+        let headers = rows.next().ok_or_else(|| String::from("the file is empty"))?;
+
+        let column = |names: &[&str]| header_column(&headers, names);
+        let club_column = column(&CLUB_HEADERS).ok_or_else(|| String::from("no column is headed \"Club\""))?;
+        let name_columns = match (column(&NAME_HEADERS), column(&FIRST_NAME_HEADERS), column(&LAST_NAME_HEADERS)) {
+            (Some(name_column), _, _) => (Some(name_column), None),
+            (None, Some(first_name_column), Some(last_name_column)) => (Some(first_name_column), Some(last_name_column)),
+            _ => return Err(String::from("no column is headed \"Name\", or \"First Name\" and \"Last Name\"")),
+        };
+
+        let entries = rows
+            .filter_map(|row| {
+                let name = match name_columns {
+                    (first_name_column, Some(last_name_column)) => format!("{} {}", cell(&row, first_name_column)?, cell(&row, Some(last_name_column))?),
+                    (name_column, None) => cell(&row, name_column)?,
+                };
+                Some(RosterEntry::new(name, cell(&row, Some(club_column))?))
+            })
+            .collect();
+
+        Ok(Roster::new(entries))
+    }
+
+    pub fn entries(&self) -> &Vec<RosterEntry> {
+        &self.entries
+    }
+
+    // The club of the skater with this name. When more than one skater on the roster has the
+    // name, the club given in the results picks between them, even if the results cut it short.
+    // None if the skater is not on the roster, or the club cannot tell the skaters apart.
+    pub fn club_of(&self, name: &str, club: &str) -> Option<&String> {
+        let name = normalize_skater_name(name);
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut clubs: Vec<&String> = vec![];
+        for entry in self.entries.iter().filter(|entry| normalize_skater_name(entry.name()) == name) {
+            if !clubs.iter().any(|existing| normalize_club_name(existing) == normalize_club_name(entry.club())) {
+                clubs.push(entry.club());
+            }
+        }
+
+        if clubs.len() < 2 {
+            return clubs.pop();
+        }
+
+        let truncated = club.trim().ends_with("...");
+        let club = normalize_club_name(club);
+        let mut matching = clubs.into_iter().filter(|roster_club| {
+            let roster_club = normalize_club_name(roster_club);
+            roster_club == club || (truncated && !club.is_empty() && roster_club.starts_with(&club))
+        });
+
+        match (matching.next(), matching.next()) {
+            (Some(roster_club), None) => Some(roster_club),
+            _ => None,
+        }
+    }
+}
+
+// Reduce a skater's name to lowercase words, turning "Last, First" into "First Last".
+fn normalize_skater_name(name: &str) -> String {
+    let name = match name.split_once(',') {
+        Some((last, first)) => format!("{} {}", first, last),
+        None => name.to_string(),
+    };
+
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// Fill in or correct the club of every skater on the roster. Skaters the roster does not settle
+// are recorded on their result, so they can be resolved by hand.
+pub fn apply_roster(result_sets: &mut [ResultSet], roster: &Roster, settings: &Settings) {
+    let roster_club = |name: &str, club: &str| {
+        roster.club_of(name, club).map(|club| settings.club_aliases.canonical(club).unwrap_or(club).clone())
+    };
+
+    for result_set in result_sets {
+        result_set.unmatched_skaters.clear();
+
+        if result_set.members.is_empty() {
+            match roster_club(&result_set.name(), &result_set.club.clone().unwrap_or_default()) {
+                Some(club) => result_set.club = Some(club),
+                None => result_set.unmatched_skaters.push(result_set.name()),
+            }
+            continue;
+        }
+
+        for member in &mut result_set.members {
+            match roster_club(&member.name, &member.club) {
+                Some(club) => member.club = club,
+                None => result_set.unmatched_skaters.push(member.name.clone()),
+            }
+        }
+        result_set.club = result_set.members.first().map(|member| member.club.clone());
+    }
+}
+
+// Every skater the roster did not settle, with the club given in the results, e.g.
+// "Eve E (Skating Club of Bo...)". Each skater is listed once.
+pub fn unmatched_skaters(result_sets: &[ResultSet]) -> Vec<String> {
+    let mut skaters: Vec<String> = vec![];
+    for result_set in result_sets {
+        for name in &result_set.unmatched_skaters {
+            let club = match result_set.members.iter().find(|member| member.name == *name) {
+                Some(member) => member.club.clone(),
+                None => result_set.club.clone().unwrap_or_default(),
+            };
+
+            let skater = if club.trim().is_empty() {
+                format!("{} (no club)", name)
+            } else {
+                format!("{} ({})", name, club)
+            };
+
+            if !skaters.contains(&skater) {
+                skaters.push(skater);
+            }
+        }
+    }
+
+    skaters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Roster;
+
+    fn roster(rows: &[[&str; 2]]) -> Roster {
+        let rows = [["Skater", "Club"]].iter().chain(rows).map(|row| row.map(String::from).to_vec()).collect();
+
+        Roster::from_rows(rows).unwrap()
+    }
+
+    #[test]
+    fn skater_with_a_unique_name_takes_the_roster_club() {
+        let roster = roster(&[["Archer, Ann", "Skating Club of Boston"]]);
+
+        assert_eq!(roster.club_of("Ann  ARCHER", ""), Some(&String::from("Skating Club of Boston")));
+        assert_eq!(roster.club_of("Ben Baker", "Skating Club of Boston"), None);
+    }
+
+    #[test]
+    fn club_in_the_results_tells_skaters_with_the_same_name_apart() {
+        let roster = roster(&[["Ann Archer", "Skating Club of Boston"], ["Ann Archer", "Los Angeles FSC"]]);
+
+        assert_eq!(roster.club_of("Ann Archer", "Los Angeles Figure Skating Club"), Some(&String::from("Los Angeles FSC")));
+        assert_eq!(roster.club_of("Ann Archer", "Skating Club of Bo..."), Some(&String::from("Skating Club of Boston")));
+        assert_eq!(roster.club_of("Ann Archer", ""), None);
+        assert_eq!(roster.club_of("Ann Archer", "Skating Club of Bo"), None);
+    }
+
+    #[test]
+    fn same_club_listed_twice_is_not_ambiguous() {
+        let roster = roster(&[["Ann Archer", "Los Angeles FSC"], ["Ann Archer", "Los Angeles Figure Skating Club"]]);
+
+        assert_eq!(roster.club_of("Ann Archer", ""), Some(&String::from("Los Angeles FSC")));
+    }
+}
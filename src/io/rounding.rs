@@ -0,0 +1,67 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde_derive::{Deserialize, Serialize};
+use crate::settings::settings::Settings;
+
+// How points are rounded to the display precision when they are written out. Points are always
+// summed exactly and only rounded for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoundingMode {
+    // Round to the nearest value, halves away from zero.
+    #[default]
+    HalfUp,
+    // Round to the nearest value, halves to the nearest even digit.
+    HalfEven,
+    // Always round toward zero.
+    Down,
+    // Always round away from zero.
+    Up,
+}
+
+impl RoundingMode {
+    pub fn strategy(&self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            RoundingMode::HalfUp => String::from("Half Up"),
+            RoundingMode::HalfEven => String::from("Half Even"),
+            RoundingMode::Down => String::from("Down"),
+            RoundingMode::Up => String::from("Up"),
+        }
+    }
+}
+
+// Round points to the display precision in the settings, without trailing zeros.
+pub fn display_points(points: Decimal, settings: &Settings) -> Decimal {
+    points
+        .round_dp_with_strategy(settings.display_precision, settings.rounding_mode.strategy())
+        .normalize()
+}
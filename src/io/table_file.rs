@@ -0,0 +1,137 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::fs;
+use std::path::Path;
+use calamine::{open_workbook_auto, Data, Reader};
+
+// Read the rows of a CSV file, or of the first sheet of a spreadsheet, as text. What the file
+// holds, e.g. "club directory", is named in any error.
+pub(crate) fn read_rows(path: &str, what: &str) -> Result<Vec<Vec<String>>, String> {
+    let is_csv = Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv") || extension.eq_ignore_ascii_case("txt"));
+
+    if is_csv {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(parse_csv(&contents)),
+            Err(err) => Err(format!("Failed to read the {} {}: {}", what, path, err)),
+        }
+    } else {
+        read_spreadsheet_rows(path, what)
+    }
+}
+
+// The first column whose header is one of the names, compared ignoring case.
+pub(crate) fn header_column(headers: &[String], names: &[&str]) -> Option<usize> {
+    headers.iter().position(|header| names.contains(&header.trim().to_lowercase().as_str()))
+}
+
+// The trimmed text of a row's cell, if the column exists and the cell is not blank.
+pub(crate) fn cell(row: &[String], column: Option<usize>) -> Option<String> {
+    column
+        .and_then(|column| row.get(column))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn read_spreadsheet_rows(path: &str, what: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut workbook = match open_workbook_auto(path) {
+        Ok(workbook) => workbook,
+        Err(_) => return Err(format!("Failed to read the {} {}. Is it a CSV file or a spreadsheet?", what, path)),
+    };
+
+    let range = match workbook.worksheet_range_at(0) {
+        Some(Ok(range)) => range,
+        _ => return Err(format!("Failed to read the first sheet of the {} {}", what, path)),
+    };
+
+    Ok(range.rows()
+        .map(|row| row.iter().map(|cell| match cell {
+            Data::Empty => String::new(),
+            cell => cell.to_string(),
+        }).collect())
+        .collect())
+}
+
+// Split CSV text into rows of fields. Fields may be quoted to contain commas, line breaks or
+// doubled quotes.
+fn parse_csv(contents: &str) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = vec![];
+    let mut row: Vec<String> = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = contents.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_csv;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter().map(|row| row.iter().map(|field| field.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn quoted_fields_keep_commas_line_breaks_and_doubled_quotes() {
+        let contents = "Name,Club\r\n\"Archer, Ann\",\"Skating Club of Boston\"\r\nBen Baker,\"The \"\"Rink\"\"\nAnnex\"\r\n";
+
+        assert_eq!(parse_csv(contents), rows(&[&["Name", "Club"], &["Archer, Ann", "Skating Club of Boston"], &["Ben Baker", "The \"Rink\"\nAnnex"]]));
+    }
+
+    #[test]
+    fn byte_order_mark_and_blank_rows_are_dropped() {
+        let contents = "\u{feff}Name,Club\n\n , \nAnn Archer,Los Angeles FSC";
+
+        assert_eq!(parse_csv(contents), rows(&[&["Name", "Club"], &["Ann Archer", "Los Angeles FSC"]]));
+    }
+
+    #[test]
+    fn trailing_empty_field_is_kept() {
+        assert_eq!(parse_csv("Ann Archer,\n"), rows(&[&["Ann Archer", ""]]));
+    }
+}
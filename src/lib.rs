@@ -0,0 +1,62 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+// The tabulation engine behind Auto Team Totals. The window and the command line interface are
+// both built on top of this library, and other tools may use it the same way:
+//
+// let competition = Competition::open("My Competition", &settings)?;
+// let standings = competition.tabulate(&Rules::from(&settings));
+// standings.output_files(&settings, competition.name());
+//
+// Only the items below are public. The parsers, scoring and writers behind them are internal.
+
+mod competition;
+mod io;
+mod settings;
+
+pub use crate::competition::competition::Competition;
+pub use crate::competition::rules::Rules;
+pub use crate::competition::standings::Standings;
+pub use crate::io::club_directory::{unknown_clubs, ClubDirectory, DirectoryClub};
+pub use crate::io::excel::scoring_system_reader::read_scoring_system_spreadsheet;
+pub use crate::io::file_utils::check_and_create_dir;
+pub use crate::io::html::club_matcher::{merge_suggestions, MergeSuggestion};
+pub use crate::io::html::club_points::{subtotal_disciplines, ClubPoints};
+pub use crate::io::html::competitor_status::CompetitorStatus;
+pub use crate::io::html::discipline::{Discipline, DisciplineMapping};
+pub use crate::io::html::event::{matches_pattern, Event, IjsResultsCounting, ResultsKind};
+pub use crate::io::html::medal_table::{medal_ranks, medal_table_places, MedalCount, StandingsMode, PLACE_NAMES};
+pub use crate::io::html::parse_error::ParseError;
+pub use crate::io::html::participation::{awards_participation_points, ParticipationBasis};
+pub use crate::io::html::points_formula::PointsFormula;
+pub use crate::io::html::points_rule::PointsRule;
+pub use crate::io::html::points_system::{ChartResolution, PointsSystemKind};
+pub use crate::io::html::result_set::ResultSet;
+pub use crate::io::html::results_sorter::{shared_ranks, sort_results, TieBreaker};
+pub use crate::io::html::scoring_system::ScoringSystem;
+pub use crate::io::html::team_entry::{CrossClubCredit, TeamMember};
+pub use crate::io::html::team_standings::TeamDefinition;
+pub use crate::io::html::tie_policy::TiePolicy;
+pub use crate::io::roster::{unmatched_skaters, Roster, RosterEntry};
+pub use crate::io::rounding::{display_points, RoundingMode};
+pub use crate::settings::club_aliases::{ClubAlias, ClubAliases};
+pub use crate::settings::settings::Settings;
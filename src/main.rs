@@ -22,16 +22,21 @@ SOFTWARE.
 
 //#![windows_subsystem = "windows"]
 
+use std::{env, process};
 use iced::{window};
 use iced::window::icon;
 use crate::ui::gui::TeamTotalsGui;
 
-mod io;
-mod settings;
-
+mod cli;
 mod ui;
 
 fn main() -> Result<(), iced::Error> {
+    // Any arguments switch to the headless command line interface.
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if !args.is_empty() {
+        process::exit(cli::cli::run(args));
+    }
+
     iced::application(TeamTotalsGui::title, TeamTotalsGui::update, TeamTotalsGui::view)
         .subscription(TeamTotalsGui::subscription)
         .theme(TeamTotalsGui::theme)
@@ -0,0 +1,113 @@
+/*
+Copyright (c) 2023 Collin Ogren
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::fs;
+use serde_derive::{Deserialize, Serialize};
+use crate::settings::settings::appdata;
+
+const CLUB_ALIASES_FILE: &str = "/club_aliases.toml";
+
+// A club name as it appears in results, e.g. "SC of Boston", and the club it stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ClubAlias {
+    pub alias: String,
+    pub club: String,
+}
+
+// The club aliases remembered between competitions. They are kept in their own file beside
+// settings.toml so that they can be shared between computers or reset without losing settings.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClubAliases {
+    pub aliases: Vec<ClubAlias>,
+    pub rejected: Vec<ClubAlias>, // Pairs of different clubs with similar names that are no longer suggested for merging.
+}
+
+impl ClubAliases {
+    // Read the aliases from file. No aliases are used if the file does not exist yet.
+    pub fn read() -> Self {
+        let (aliases_file, _aliases_dir) = appdata(CLUB_ALIASES_FILE);
+        let contents = match fs::read_to_string(aliases_file) {
+            Ok(v) => v,
+            Err(_) => return ClubAliases::default(),
+        };
+
+        toml::from_str(&contents).unwrap_or_else(|err| {
+            eprintln!("Failed to read club aliases file: {}\nNo club aliases will be used.", err);
+            ClubAliases::default()
+        })
+    }
+
+    // Write the aliases to their TOML file.
+    pub fn write(&self) {
+        let (aliases_file, _aliases_dir) = appdata(CLUB_ALIASES_FILE);
+        let toml = match toml::to_string(self) {
+            Ok(toml) => { toml }
+            Err(err) => {
+                eprintln!("Failed to serialize club aliases: {}", err);
+                return
+            }
+        };
+
+        if let Err(err) = fs::write(aliases_file, toml) {
+            eprintln!("Failed to write to club_aliases.toml: {}", err)
+        }
+    }
+
+    // The club a name from the results stands for. Names are compared ignoring case and extra
+    // whitespace. Aliases without a club are still being entered and are ignored.
+    pub fn canonical(&self, name: &str) -> Option<&String> {
+        let name = normalize(name);
+        self.aliases
+            .iter()
+            .find(|alias| !alias.club.trim().is_empty() && normalize(&alias.alias) == name)
+            .map(|alias| &alias.club)
+    }
+
+    // Map an alias to a club, replacing any club it was mapped to before.
+    pub fn set(&mut self, alias: &str, club: &str) {
+        let normalized = normalize(alias);
+        match self.aliases.iter_mut().find(|existing| normalize(&existing.alias) == normalized) {
+            Some(existing) => existing.club = club.to_string(),
+            None => self.aliases.push(ClubAlias { alias: alias.to_string(), club: club.to_string() }),
+        }
+    }
+
+    // Remember that two clubs with similar names are different clubs.
+    pub fn reject(&mut self, club: &str, other: &str) {
+        if !self.is_rejected(club, other) {
+            self.rejected.push(ClubAlias { alias: club.to_string(), club: other.to_string() });
+        }
+    }
+
+    pub fn is_rejected(&self, club: &str, other: &str) -> bool {
+        let (club, other) = (normalize(club), normalize(other));
+        self.rejected.iter().any(|rejected| {
+            let (a, b) = (normalize(&rejected.alias), normalize(&rejected.club));
+            (a == club && b == other) || (a == other && b == club)
+        })
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
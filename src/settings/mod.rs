@@ -1 +1,2 @@
-pub mod settings;
\ No newline at end of file
+pub mod club_aliases;
+pub mod settings;
@@ -23,7 +23,22 @@ SOFTWARE.
 use std::fs;
 use std::path::Path;
 use directories::{ProjectDirs, UserDirs};
+use rust_decimal::Decimal;
 use serde_derive::{Deserialize, Serialize};
+use crate::io::html::competitor_status::CompetitorStatus;
+use crate::io::html::discipline::DisciplineMapping;
+use crate::io::html::event::IjsResultsCounting;
+use crate::io::html::medal_table::StandingsMode;
+use crate::io::html::participation::ParticipationBasis;
+use crate::io::html::points_formula::PointsFormula;
+use crate::io::html::points_rule::PointsRule;
+use crate::io::html::points_system::{ChartResolution, PointsSystemKind};
+use crate::io::html::results_sorter::TieBreaker;
+use crate::io::html::team_entry::CrossClubCredit;
+use crate::io::html::team_standings::TeamDefinition;
+use crate::io::html::tie_policy::TiePolicy;
+use crate::io::rounding::RoundingMode;
+use crate::settings::club_aliases::ClubAliases;
 
 const SETTINGS_FILE: &'static str = "/settings.toml";
 
@@ -48,44 +63,98 @@ pub fn documents() -> String {
     }
 }
 
-// Serializable struct to store all permanent program settings.
-#[derive(Clone)]
+// Serializable struct to store all permanent program settings. Settings missing from an older
+// settings.toml file take their default values.
+#[derive(Clone, Debug)]
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
-    pub(crate) default_points_system: Vec<f64>,
-    pub(crate) include_60: bool,
-    pub(crate) include_ijs: bool,
-    pub(crate) generate_xlsx: bool,
-    pub(crate) generate_xlsx_info_dump: bool,
-    pub(crate) generate_html: bool,
-    pub(crate) use_scoring_system_spreadsheet: bool,
-    pub(crate) attempt_automatic_60_club_name_recombination: bool,
-    pub(crate) use_event_name_for_results_path: bool, //If this is set to true, then the program will find the results based on event name rather than absolute path.
-    pub(crate) isu_calc_base_directory: String,
-    pub(crate) html_relative_directory: String,
-    pub(crate) output_directory: String,
-    pub(crate) xlsx_file_name: String,
-    pub(crate) xlsx_info_dump_file_name: String,
-    pub(crate) html_file_name: String,
-    pub(crate) scoring_system_file_name: Option<String>,
-    pub(crate) xlsx_header_cell_values: Vec<String>,
-    pub(crate) xlsx_info_dump_header_cell_values: Vec<String>,
-    pub(crate) xlsx_column_widths: Vec<i32>,
-    pub(crate) xlsx_font_size: u32,
+    pub default_points_system: Vec<Decimal>,
+    pub tie_policy: TiePolicy,
+    pub standings_mode: StandingsMode,
+    pub medal_table_places: u64, // Places counted in the medal table, from 3 for the podium up to 6.
+    pub standings_tie_breakers: Vec<TieBreaker>, // Separate clubs with equal totals, in order. Clubs still tied share a rank.
+    pub cross_club_credit: CrossClubCredit,
+    pub team_definitions: Vec<TeamDefinition>, // Teams made up of several clubs, ranked in team standings alongside the clubs.
+    pub group_teams_by_region: bool, // Clubs on no defined team are on the team of their region in the club directory.
+    pub include_60: bool,
+    pub include_ijs: bool,
+    pub ijs_results_counting: IjsResultsCounting,
+    pub statuses_counted_in_field_size: Vec<CompetitorStatus>, // Which entries count toward the number of participants in an event.
+    pub generate_xlsx: bool,
+    pub generate_xlsx_info_dump: bool,
+    pub generate_html: bool,
+    pub points_system: PointsSystemKind,
+    pub discipline_mappings: Vec<DisciplineMapping>, // Disciplines for events whose names do not give them away. The first matching pattern wins.
+    pub show_discipline_subtotals: bool, // Show each club's points in every discipline in the output files.
+    pub points_rules: Vec<PointsRule>, // Checked in order when scoring with event rules. Events no rule matches use default_points_system.
+    pub points_formula: String, // Points as an expression of rank and participants when scoring with a formula.
+    pub chart_resolution: ChartResolution, // What to do when the points chart has no column for an event's field size.
+    pub participant_quantity_exclusion_point: u64, // Events with fewer participants than this award reduced points. 0 disables the minimum.
+    pub small_field_points_multiplier: Decimal, // Multiplier for the points of events below the minimum field size. 0 awards no points.
+    pub max_scoring_entries_per_club: u64, // Only a club's best finishers up to this many count in each event. 0 counts every entry.
+    pub participation_points: Decimal, // Points on top of placement points for taking part. 0 awards none.
+    pub participation_basis: ParticipationBasis,
+    pub max_counted_results_per_skater: u64, // Only a skater's best results up to this many count toward the totals. 0 counts every result.
+    pub attempt_automatic_60_club_name_recombination: bool,
+    #[serde(skip)]
+    pub club_aliases: ClubAliases, // Stored in club_aliases.toml beside this file and applied to every club name in the results.
+    pub club_match_threshold: u64, // Clubs whose names are at least this alike, in percent, are suggested for merging. Above 100 disables suggestions.
+    pub use_event_name_for_results_path: bool, //If this is set to true, then the program will find the results based on event name rather than absolute path.
+    pub isu_calc_base_directory: String,
+    pub html_relative_directory: String,
+    pub output_directory: String,
+    pub xlsx_file_name: String,
+    pub xlsx_info_dump_file_name: String,
+    pub html_file_name: String,
+    pub scoring_system_file_name: Option<String>,
+    pub roster_file_name: Option<String>, // The skaters entered in the competition and their clubs, as a CSV file or spreadsheet. Fills in or corrects the club of each skater.
+    pub club_directory_file_name: Option<String>, // The governing body's list of clubs, as a CSV file or spreadsheet. Club names in the results are replaced with their official names.
+    pub xlsx_header_cell_values: Vec<String>,
+    pub xlsx_participation_header_cell_value: String, // Header of the participation points column, placed before the total when participation points are awarded.
+    pub xlsx_team_header_cell_value: String, // Header of the team column on the team standings sheet, which takes the place of the club column.
+    pub xlsx_directory_header_cell_values: Vec<String>, // Headers of the club number and region columns, placed last when a club directory is used.
+    pub xlsx_info_dump_header_cell_values: Vec<String>,
+    pub xlsx_column_widths: Vec<i32>,
+    pub xlsx_font_size: u32,
+    pub display_precision: u32, // Decimal places that points are rounded to in the output files.
+    pub rounding_mode: RoundingMode,
 }
 
 // Default settings
 impl Default for Settings {
     fn default() -> Self {
         Self {
-            default_points_system: vec![3.0, 2.0, 1.0],
+            default_points_system: vec![Decimal::from(3), Decimal::from(2), Decimal::from(1)],
+            tie_policy: TiePolicy::default(),
+            standings_mode: StandingsMode::default(),
+            medal_table_places: 3,
+            standings_tie_breakers: vec![TieBreaker::MostFirstPlaces, TieBreaker::MostSecondPlaces],
+            cross_club_credit: CrossClubCredit::default(),
+            team_definitions: vec![],
+            group_teams_by_region: false,
             include_60: true,
             include_ijs: true,
+            ijs_results_counting: IjsResultsCounting::default(),
+            statuses_counted_in_field_size: vec![CompetitorStatus::Placed],
             generate_xlsx: true,
             generate_html: true,
             generate_xlsx_info_dump: false,
-            use_scoring_system_spreadsheet: false,
+            points_system: PointsSystemKind::default(),
+            points_rules: vec![],
+            discipline_mappings: vec![],
+            show_discipline_subtotals: false,
+            points_formula: String::from("max(0, participants - rank + 1)"),
+            chart_resolution: ChartResolution::default(),
+            participant_quantity_exclusion_point: 0,
+            small_field_points_multiplier: Decimal::ZERO,
+            max_scoring_entries_per_club: 0,
+            max_counted_results_per_skater: 0,
+            participation_points: Decimal::ZERO,
+            participation_basis: ParticipationBasis::default(),
             attempt_automatic_60_club_name_recombination: true,
+            club_aliases: ClubAliases::default(),
+            club_match_threshold: 80,
             use_event_name_for_results_path: true,
             isu_calc_base_directory: String::from("C:/ISUCalcFS/"),
             html_relative_directory: String::from("/IJScompanion_html_winnercomm"),
@@ -94,25 +163,53 @@ impl Default for Settings {
             xlsx_info_dump_file_name: String::from("team_totals_info_dump.xlsx"),
             html_file_name: String::from("team_totals.html"),
             scoring_system_file_name: None,
+            roster_file_name: None,
+            club_directory_file_name: None,
             xlsx_header_cell_values: vec![String::from("Rank"), String::from("Club"), String::from("IJS"), String::from("6.0"), String::from("Total")],
-            xlsx_info_dump_header_cell_values: vec![String::from("Event"), String::from("Skater"), String::from("Club"), String::from("Rank"), String::from("Total Participants"), String::from("Points"), String::from("Scoring System")],
+            xlsx_participation_header_cell_value: String::from("Participation"),
+            xlsx_team_header_cell_value: String::from("Team"),
+            xlsx_directory_header_cell_values: vec![String::from("Club Number"), String::from("Region")],
+            xlsx_info_dump_header_cell_values: vec![String::from("Event"), String::from("Skater"), String::from("Club"), String::from("Rank"), String::from("Total Participants"), String::from("Points"), String::from("Scoring System"), String::from("Status"), String::from("Notes")],
             xlsx_column_widths: vec![15, 100, 11, 11, 15],
             xlsx_font_size: 32,
+            display_precision: 2,
+            rounding_mode: RoundingMode::default(),
         }
     }
 }
  impl Settings {
      #[allow(unused)]
     pub fn new(
-        points_for_each_placement: Vec<f64>,
+        points_for_each_placement: Vec<Decimal>,
+        tie_policy: TiePolicy,
+        standings_mode: StandingsMode,
+        medal_table_places: u64,
+        standings_tie_breakers: Vec<TieBreaker>,
+        cross_club_credit: CrossClubCredit,
+        team_definitions: Vec<TeamDefinition>,
+        group_teams_by_region: bool,
         include_60: bool,
         include_ijs: bool,
+        ijs_results_counting: IjsResultsCounting,
+        statuses_counted_in_field_size: Vec<CompetitorStatus>,
         generate_xlsx: bool,
         generate_xlsx_info_dump: bool,
         generate_html: bool,
-        use_scoring_system_spreadsheet: bool,
+        points_system: PointsSystemKind,
+        points_rules: Vec<PointsRule>,
+        discipline_mappings: Vec<DisciplineMapping>,
+        show_discipline_subtotals: bool,
+        points_formula: String,
+        chart_resolution: ChartResolution,
         participant_quantity_exclusion_point: u64,
+        small_field_points_multiplier: Decimal,
+        max_scoring_entries_per_club: u64,
+        max_counted_results_per_skater: u64,
+        participation_points: Decimal,
+        participation_basis: ParticipationBasis,
         attempt_automatic_60_club_name_recombination: bool,
+        club_aliases: ClubAliases,
+        club_match_threshold: u64,
         use_event_name_for_results_path: bool,
         isu_calc_base_directory: String,
         html_relative_directory: String,
@@ -121,20 +218,49 @@ impl Default for Settings {
         xlsx_info_dump_file_name: String,
         html_file_name: String,
         scoring_system_file_name: Option<String>,
+        roster_file_name: Option<String>,
+        club_directory_file_name: Option<String>,
         xlsx_header_cell_values: Vec<String>,
+        xlsx_participation_header_cell_value: String,
+        xlsx_team_header_cell_value: String,
+        xlsx_directory_header_cell_values: Vec<String>,
         xlsx_info_dump_header_cell_values: Vec<String>,
         xlsx_column_widths: Vec<i32>,
         xlsx_font_size: u32,
+        display_precision: u32,
+        rounding_mode: RoundingMode,
     ) -> Self {
         Settings {
             default_points_system: points_for_each_placement,
+            tie_policy,
+            standings_mode,
+            medal_table_places,
+            standings_tie_breakers,
+            cross_club_credit,
+            team_definitions,
+            group_teams_by_region,
             include_60,
             include_ijs,
+            ijs_results_counting,
+            statuses_counted_in_field_size,
             generate_xlsx,
             generate_xlsx_info_dump,
             generate_html,
-            use_scoring_system_spreadsheet,
+            points_system,
+            points_rules,
+            discipline_mappings,
+            show_discipline_subtotals,
+            points_formula,
+            chart_resolution,
+            participant_quantity_exclusion_point,
+            small_field_points_multiplier,
+            max_scoring_entries_per_club,
+            max_counted_results_per_skater,
+            participation_points,
+            participation_basis,
             attempt_automatic_60_club_name_recombination,
+            club_aliases,
+            club_match_threshold,
             use_event_name_for_results_path,
             isu_calc_base_directory,
             html_relative_directory,
@@ -143,10 +269,17 @@ impl Default for Settings {
             xlsx_info_dump_file_name,
             html_file_name,
             scoring_system_file_name,
+            roster_file_name,
+            club_directory_file_name,
             xlsx_header_cell_values,
+            xlsx_participation_header_cell_value,
+            xlsx_team_header_cell_value,
+            xlsx_directory_header_cell_values,
             xlsx_info_dump_header_cell_values,
             xlsx_column_widths,
             xlsx_font_size,
+            display_precision,
+            rounding_mode,
         }
     }
 
@@ -176,16 +309,50 @@ impl Default for Settings {
             }
         };
 
-        let settings: Settings = toml::from_str(&contents).unwrap_or_else(|err| {
+        let mut settings: Settings = toml::from_str(&contents).unwrap_or_else(|err| {
             eprintln!("Failed to read settings file: {}\nUsing default values.", err);
             Settings::default()
         });
 
+        // Older settings files chose between the single points table and the spreadsheet with
+        // use_scoring_system_spreadsheet instead of naming a points system.
+        if let Ok(table) = contents.parse::<toml::Table>() {
+            if !table.contains_key("points_system") && table.get("use_scoring_system_spreadsheet").and_then(|value| value.as_bool()) == Some(true) {
+                settings.points_system = PointsSystemKind::FieldSizeChart;
+            }
+        }
+
+        if let Err(err) = settings.check_points_formulas() {
+            eprintln!("{}", err);
+        }
+
+        settings.club_aliases = ClubAliases::read();
+
         settings
     }
 
-     // Write the TOML file from the serializable struct.
+     // Make sure the points formula and the formulas of the points rules can be used, so a
+     // mistake is reported when the settings are loaded rather than part way through scoring.
+    pub fn check_points_formulas(&self) -> Result<(), String> {
+        if let Err(err) = PointsFormula::parse(&self.points_formula) {
+            return Err(format!("Invalid points formula \"{}\": {}", self.points_formula, err));
+        }
+
+        for rule in &self.points_rules {
+            if let Some(formula) = rule.formula.as_ref().filter(|formula| !formula.trim().is_empty()) {
+                if let Err(err) = PointsFormula::parse(formula) {
+                    return Err(format!("Invalid formula \"{}\" in the points rule for {}: {}", formula, rule.describe(), err));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+     // Write the TOML file from the serializable struct, along with the club aliases.
     pub fn write(&self) {
+        self.club_aliases.write();
+
         let (settings_file, _settings_dir) = appdata(SETTINGS_FILE);
         let toml = match toml::to_string(self) {
             Ok(toml) => { toml }
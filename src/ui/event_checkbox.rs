@@ -1,6 +1,6 @@
 use iced::{Alignment, Element};
 use iced::widget::{checkbox, row, text};
-use crate::io::html::event::Event;
+use auto_team_totals::Event;
 
 #[derive(Debug, Clone)]
 pub enum EventToInclude {
@@ -22,8 +22,8 @@ impl EventCheckbox {
     }
 
     pub fn view<'a>(&'a self) -> Element<'a, EventToInclude> {
-        let checkbox = checkbox("", self.event.active).on_toggle(EventToInclude::Edited);
+        let checkbox = checkbox("", self.event.active()).on_toggle(EventToInclude::Edited);
 
-        row![checkbox, text(&self.event.event_name).align_x(Alignment::Center).height(30)].align_y(Alignment::Center).into()
+        row![checkbox, text(self.event.event_name()).align_x(Alignment::Center).height(30)].align_y(Alignment::Center).into()
     }
 }
\ No newline at end of file
@@ -22,25 +22,18 @@ SOFTWARE.
 
 use std::{thread, vec};
 use std::ffi::OsStr;
-use std::io::ErrorKind;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use iced::{Alignment, Element, keyboard, Renderer, Subscription, Task, Theme, widget, window};
 use iced::alignment::Vertical;
 use iced::keyboard::key::Named;
-use iced::widget::{Button, Checkbox, column, Column, container, horizontal_space, keyed_column, radio, row, Scrollable, scrollable, text, Text, text_input, vertical_rule, vertical_space};
+use iced::widget::{Button, Checkbox, column, Column, container, horizontal_space, keyed_column, pick_list, radio, row, Scrollable, scrollable, text, Text, text_input, vertical_rule, vertical_space};
 use iced::widget::scrollable::RelativeOffset;
 use native_dialog::FileDialogBuilder;
+use rust_decimal::Decimal;
 use once_cell::sync::Lazy;
-use crate::io::excel::scoring_system_reader;
-use crate::io::file_utils;
-use crate::io::html::{event, parser};
-use crate::io::html::club_points::ClubPoints;
-use crate::io::html::event::Event;
-use crate::io::html::parser::State;
-use crate::io::html::result_set::ResultSet;
-use crate::io::html::results_sorter::sort_results;
-use crate::settings::settings::{Settings};
+use auto_team_totals::{ChartResolution, ClubAlias, ClubDirectory, ClubPoints, Competition, CompetitorStatus, CrossClubCredit, Discipline, DisciplineMapping, IjsResultsCounting, MergeSuggestion, ParseError, ParticipationBasis, PointsFormula, PointsRule, PointsSystemKind, ResultSet, Roster, RoundingMode, Rules, ScoringSystem, Settings, Standings, StandingsMode, TeamDefinition, TieBreaker, TiePolicy};
+use auto_team_totals::{awards_participation_points, check_and_create_dir, display_points, merge_suggestions, read_scoring_system_spreadsheet, shared_ranks, sort_results, subtotal_disciplines, unknown_clubs, unmatched_skaters};
 use crate::ui::event_checkbox::{EventCheckbox, EventToInclude};
 use crate::ui::gui::TeamTotalsMessage::NoneInput;
 use crate::ui::text_field::{ClubPointsEdit, ClubPointsField, PointsField, PointsForEachPlacement, TextField};
@@ -49,6 +42,7 @@ static COMPETITION_INPUT_ID: Lazy<text_input::Id> = Lazy::new(competition_input_
 static SCROLLABLE_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
 
 const PLACEMENT_WIDTH: f32 = 50.0;
+const DIAGNOSTICS_HEIGHT: f32 = 120.0;
 
 fn competition_input_id() -> text_input::Id {
     text_input::Id::new(String::from("competition_input"))
@@ -63,11 +57,22 @@ pub struct TeamTotalsGui {
     competition: String,
     settings: Settings,
     status: String,
+    diagnostics: Vec<ParseError>,
     theme: Theme,
     font_size: String,
+    minimum_field_size: String,
+    small_field_points_multiplier: String,
+    max_scoring_entries_per_club: String,
+    max_counted_results_per_skater: String,
+    participation_points: String,
+    medal_table_places: String,
+    club_match_threshold: String,
+    display_precision: String,
     points_for_each_placement: Vec<PointsField>,
+    points_rule_points: Vec<String>,
+    team_definition_clubs: Vec<String>,
 
-    events: Vec<Event>,
+    opened_competition: Option<Competition>,
     event_controls: Vec<EventCheckbox>,
     last_checkbox: isize,
     is_shift_down: bool,
@@ -79,10 +84,14 @@ pub struct TeamTotalsGui {
     club_name_edits: Vec<ClubPointsField>,
     club_points_60_edits: Vec<ClubPointsField>,
     club_points_ijs_edits: Vec<ClubPointsField>,
+    club_points_participation_edits: Vec<ClubPointsField>,
 
     result_sets: Vec<ResultSet>,
+    standings: Option<Standings>,
+    merge_suggestions: Vec<MergeSuggestion>,
+    unknown_clubs: Vec<String>,
+    unmatched_skaters: Vec<String>,
 
-    selected_scoring_system_type: Option<ScoringSystemChoice>,
 }
 
 #[derive(Debug, Clone)]
@@ -97,7 +106,20 @@ pub enum TeamTotalsMessage {
     GenerateXLSX(bool),
     GenerateXLSXInfoDump(bool),
     GenerateHTML(bool),
+    ShowDisciplineSubtotals(bool),
+    GroupTeamsByRegion(bool),
     XLSXFontSize(String),
+    MinimumFieldSize(String),
+    SmallFieldPointsMultiplier(String),
+    MaxScoringEntriesPerClub(String),
+    MaxCountedResultsPerSkater(String),
+    ParticipationPoints(String),
+    ParticipationBasisSelected(ParticipationBasis),
+    StandingsModeSelected(StandingsMode),
+    MedalTablePlaces(String),
+    ClubMatchThreshold(String),
+    DisplayPrecision(String),
+    PointsFormula(String),
     ISUCalcBaseDirectory(String),
     HTMLRelativeDirectory(String),
     XLSXFileName(String),
@@ -105,8 +127,8 @@ pub enum TeamTotalsMessage {
     OutputDirectory(String),
     PointsForEachPlacement(usize, PointsForEachPlacement),
     EventInclusionChanged(usize, EventToInclude),
-    EventsRetrieved((Vec<Event>, String)),
-    ResultsRetrieved((Vec<ClubPoints>, Vec<ResultSet>, String)),
+    EventsRetrieved(Result<Competition, String>),
+    ResultsRetrieved(Standings),
 
     TabPressed { shift: bool },
     FindReceived(Option<PathBuf>),
@@ -115,6 +137,10 @@ pub enum TeamTotalsMessage {
     FindOutputDirectory,
     FindSpreadsheetDirectory,
     FindSpreadsheetDirectoryReceived(Option<PathBuf>),
+    FindRoster,
+    FindRosterReceived(Option<PathBuf>),
+    FindClubDirectory,
+    FindClubDirectoryReceived(Option<PathBuf>),
     OpenInFileViewer,
 
     AddPlacement,
@@ -128,78 +154,71 @@ pub enum TeamTotalsMessage {
     ClubNameEdited(usize, ClubPointsEdit),
     ClubPointsIJSEdited(usize, ClubPointsEdit),
     ClubPoints60Edited(usize, ClubPointsEdit),
+    ClubPointsParticipationEdited(usize, ClubPointsEdit),
     NoneInput(String),
 
-    ScoringSystemSelected(ScoringSystemChoice),
+    PointsSystemSelected(PointsSystemKind),
+    ChartResolutionSelected(ChartResolution),
+    TiePolicySelected(TiePolicy),
+    AddTieBreaker(String),
+    RemoveTieBreaker(usize),
+    RaiseTieBreaker(usize),
+    CrossClubCreditSelected(CrossClubCredit),
+    IjsResultsCountingSelected(IjsResultsCounting),
+    FieldSizeStatusToggled(CompetitorStatus, bool),
+    RoundingModeSelected(RoundingMode),
+    AddPointsRule,
+    RemovePointsRule(usize),
+    PointsRuleEdited(usize, PointsRuleEdit),
+    AddDisciplineMapping,
+    RemoveDisciplineMapping(usize),
+    DisciplineMappingEdited(usize, DisciplineMappingEdit),
+    AddClubAlias(String),
+    RemoveClubAlias(usize),
+    ClubAliasEdited(usize, ClubAliasEdit),
+    AddTeamDefinition,
+    RemoveTeamDefinition(usize),
+    TeamDefinitionEdited(usize, TeamDefinitionEdit),
+    AcceptMergeSuggestion(usize),
+    RejectMergeSuggestion(usize),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ScoringSystemChoice {
-    Single,
-    Spreadsheet,
+#[derive(Debug, Clone)]
+pub enum TeamDefinitionEdit {
+    Team(String),
+    Clubs(String),
 }
 
-fn get_directory(input: String, settings: &Settings) -> Result<String, ErrorKind> {
-    let result;
-    if settings.use_event_name_for_results_path {
-        let isu_calc_base_directory = if !settings.isu_calc_base_directory.ends_with("/") && !settings.isu_calc_base_directory.ends_with("\\") {
-            format!("{}{}", settings.isu_calc_base_directory, "/")
-        } else {
-            settings.isu_calc_base_directory.clone()
-        };
-
-        let html_relative_directory = if !settings.html_relative_directory.starts_with("/") && !settings.html_relative_directory.starts_with("\\") {
-            format!("{}{}", "/", settings.html_relative_directory)
-        } else {
-            settings.html_relative_directory.clone()
-        };
-
-        let path_string = format!("{}{}{}", isu_calc_base_directory, input, html_relative_directory).replace("\r", "").replace("\n", "");
-        let path = Path::new(path_string.as_str());
-
-        if path.is_dir() {
-            result = Ok(path_string);
-        } else {
-            result = Err(ErrorKind::NotFound)
-        }
-
-        result
-    } else {
-        let path_string = format!("{}{}", String::from(input).replace("\r", "").replace("\n", ""), settings.html_relative_directory);
-        let path = Path::new(path_string.as_str());
-
-        if path.is_dir() {
-            result = Ok(path_string);
-        } else {
-            result = Err(ErrorKind::NotFound);
-        }
-
-        result
-    }
+#[derive(Debug, Clone)]
+pub enum ClubAliasEdit {
+    Alias(String),
+    Club(String),
 }
 
-fn retrieve_events(competition: String, settings: Settings) -> (Vec<Event>, String) {
-    let path = match get_directory(competition, &settings) {
-        Ok(path) => path,
-        Err(_) => return (vec![], String::from("No competition found.")), // Should send signal to the user that no path is found.
-    };
-
-    let (events, output, state) = event::retrieve_events(path);
-
-    match state {
-        State::Ok => (events, output),
-        State::Error => (events, output)
-    }
+#[derive(Debug, Clone)]
+pub enum DisciplineMappingEdit {
+    EventPattern(String),
+    Discipline(String),
 }
 
-fn calculate(events: Vec<Event>, settings: &Settings) -> (Vec<ClubPoints>, Vec<ResultSet>, String, State) {
-    let result = parser::parse_results(events, settings);
-    result
+#[derive(Debug, Clone)]
+pub enum PointsRuleEdit {
+    EventPattern(String),
+    Level(String),
+    Discipline(String),
+    ScoringSystem(String),
+    Points(String),
+    Sheet(String),
+    Formula(String),
 }
 
+const ANY_DISCIPLINE: &str = "Any Discipline";
+const ANY_SCORING_SYSTEM: &str = "Any Scoring System";
+const FORMULA_PREVIEW_PARTICIPANTS: u64 = 5;
+
 impl TeamTotalsGui {
     fn apply_points_for_each_placement(&mut self, value: String, index: usize) {
-        match str::parse::<f64>(value.as_str()) {
+        match str::parse::<Decimal>(value.as_str()) {
             Ok(_) => {
                 self.points_for_each_placement[index] = PointsField::new(index, value, None);
             }
@@ -219,7 +238,7 @@ impl TeamTotalsGui {
         self.settings.default_points_system.clear();
 
         for points in &self.points_for_each_placement {
-            self.settings.default_points_system.push(str::parse::<f64>(points.value.as_str()).unwrap_or_else(|_| 0.0));
+            self.settings.default_points_system.push(str::parse::<Decimal>(points.value.as_str()).unwrap_or_default());
         }
     }
 
@@ -229,6 +248,271 @@ impl TeamTotalsGui {
         for (i, points) in self.settings.default_points_system.iter().enumerate() {
             self.points_for_each_placement.push(PointsField::new(i, format!("{}", points), None));
         }
+
+        self.points_rule_points = self.settings.points_rules
+            .iter()
+            .map(|rule| rule.points.iter().map(|points| points.to_string()).collect::<Vec<String>>().join(", "))
+            .collect();
+
+        self.team_definition_clubs = self.settings.team_definitions
+            .iter()
+            .map(|definition| definition.clubs.join("; "))
+            .collect();
+    }
+
+    fn apply_points_rule_edit(&mut self, i: usize, edit: PointsRuleEdit) {
+        let rule = match self.settings.points_rules.get_mut(i) {
+            Some(rule) => rule,
+            None => return,
+        };
+
+        match edit {
+            PointsRuleEdit::EventPattern(event_pattern) => rule.event_pattern = event_pattern,
+            PointsRuleEdit::Level(level) => rule.level = level,
+            PointsRuleEdit::Discipline(name) => {
+                rule.discipline = Discipline::ALL.into_iter().find(|discipline| discipline.get_name() == name);
+            }
+            PointsRuleEdit::ScoringSystem(name) => {
+                rule.scoring_system = [ScoringSystem::IJS, ScoringSystem::SixO].into_iter().find(|scoring_system| scoring_system.get_name() == name);
+            }
+            PointsRuleEdit::Points(value) => {
+                // Keep what was typed even while it is not a valid list yet.
+                let points = value
+                    .split(',')
+                    .filter(|points| !points.trim().is_empty())
+                    .map(|points| points.trim().parse::<Decimal>())
+                    .collect::<Result<Vec<Decimal>, _>>();
+
+                if let Ok(points) = points {
+                    rule.points = points;
+                }
+                self.points_rule_points[i] = value;
+            }
+            PointsRuleEdit::Sheet(sheet) => {
+                rule.sheet = if sheet.is_empty() { None } else { Some(sheet) };
+            }
+            PointsRuleEdit::Formula(formula) => {
+                rule.formula = if formula.is_empty() { None } else { Some(formula) };
+            }
+        }
+    }
+
+    fn points_rules_view(&self) -> widget::Column<'_, TeamTotalsMessage> {
+        let mut rules_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Event Rules"),
+            text("The first rule matching an event sets its points. Other events use the points below."),
+            vertical_space().height(5),
+        ];
+
+        let discipline_options = std::iter::once(String::from(ANY_DISCIPLINE))
+            .chain(Discipline::ALL.iter().map(|discipline| discipline.get_name()))
+            .collect::<Vec<String>>();
+        let scoring_system_options = vec![String::from(ANY_SCORING_SYSTEM), ScoringSystem::IJS.get_name(), ScoringSystem::SixO.get_name()];
+
+        for (i, rule) in self.settings.points_rules.iter().enumerate() {
+            let selected_discipline = rule.discipline.map_or(String::from(ANY_DISCIPLINE), |discipline| discipline.get_name());
+            let selected_scoring_system = rule.scoring_system.as_ref().map_or(String::from(ANY_SCORING_SYSTEM), |scoring_system| scoring_system.get_name());
+
+            let rule_column: widget::Column<'_, TeamTotalsMessage> = column![
+                row![
+                    text(format!("Rule {}", i + 1)).align_y(Vertical::Center).height(30),
+                    horizontal_space(),
+                    Button::new(Text::new("Remove")).on_press(TeamTotalsMessage::RemovePointsRule(i)),
+                ],
+                text_input("Event name pattern, e.g. Senior*", &rule.event_pattern)
+                    .on_input(move |value| TeamTotalsMessage::PointsRuleEdited(i, PointsRuleEdit::EventPattern(value))),
+                text_input("Level, e.g. Pre-Preliminary", &rule.level)
+                    .on_input(move |value| TeamTotalsMessage::PointsRuleEdited(i, PointsRuleEdit::Level(value))),
+                row![
+                    pick_list(discipline_options.clone(), Some(selected_discipline), move |value| TeamTotalsMessage::PointsRuleEdited(i, PointsRuleEdit::Discipline(value))).width(iced::Length::Fill),
+                    pick_list(scoring_system_options.clone(), Some(selected_scoring_system), move |value| TeamTotalsMessage::PointsRuleEdited(i, PointsRuleEdit::ScoringSystem(value))).width(iced::Length::Fill),
+                ],
+                text_input("Points for each placement, e.g. 5, 3, 1", &self.points_rule_points[i])
+                    .on_input(move |value| TeamTotalsMessage::PointsRuleEdited(i, PointsRuleEdit::Points(value))),
+                text_input("Spreadsheet sheet to use instead (optional)", rule.sheet.as_deref().unwrap_or(""))
+                    .on_input(move |value| TeamTotalsMessage::PointsRuleEdited(i, PointsRuleEdit::Sheet(value))),
+                text_input("Formula to use instead (optional), e.g. participants - rank + 1", rule.formula.as_deref().unwrap_or(""))
+                    .on_input(move |value| TeamTotalsMessage::PointsRuleEdited(i, PointsRuleEdit::Formula(value))),
+            ].spacing(2);
+
+            let rule_column = match rule.formula.as_deref().filter(|formula| !formula.trim().is_empty()).map(PointsFormula::parse) {
+                Some(Err(err)) => rule_column.push(text(err).style(text::danger)),
+                _ => rule_column,
+            };
+
+            rules_column = rules_column.push(rule_column).push(vertical_space().height(10));
+        }
+
+        let add_rule = Button::new(Text::new("Add Rule").align_x(Alignment::Center)).on_press(TeamTotalsMessage::AddPointsRule).width(iced::Length::Fill);
+
+        rules_column.push(add_rule)
+    }
+
+    fn discipline_mappings_view(&self) -> widget::Column<'_, TeamTotalsMessage> {
+        let mut mappings_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Event Disciplines"),
+            text("Events matching a pattern below count toward its discipline. Other events are classified by name."),
+            vertical_space().height(5),
+        ];
+
+        let discipline_options = Discipline::ALL.iter().map(|discipline| discipline.get_name()).collect::<Vec<String>>();
+
+        for (i, mapping) in self.settings.discipline_mappings.iter().enumerate() {
+            mappings_column = mappings_column.push(row![
+                text_input("Event name pattern, e.g. *Solo Dance*", &mapping.event_pattern)
+                    .on_input(move |value| TeamTotalsMessage::DisciplineMappingEdited(i, DisciplineMappingEdit::EventPattern(value)))
+                    .width(iced::Length::FillPortion(2)),
+                pick_list(discipline_options.clone(), Some(mapping.discipline.get_name()), move |value| TeamTotalsMessage::DisciplineMappingEdited(i, DisciplineMappingEdit::Discipline(value))).width(iced::Length::Fill),
+                Button::new(Text::new("Remove")).on_press(TeamTotalsMessage::RemoveDisciplineMapping(i)),
+            ].spacing(2)).push(vertical_space().height(2));
+        }
+
+        let add_mapping = Button::new(Text::new("Add Event Discipline").align_x(Alignment::Center)).on_press(TeamTotalsMessage::AddDisciplineMapping).width(iced::Length::Fill);
+
+        mappings_column.push(add_mapping)
+    }
+
+    fn team_definitions_view(&self) -> widget::Column<'_, TeamTotalsMessage> {
+        let mut teams_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Teams"),
+            text("Teams are ranked by the points of their clubs, alongside the clubs. A club counts toward the first team listing it."),
+            vertical_space().height(5),
+        ];
+
+        for (i, definition) in self.settings.team_definitions.iter().enumerate() {
+            let team_column: widget::Column<'_, TeamTotalsMessage> = column![
+                row![
+                    text_input("Team name, e.g. Eastern Section", &definition.team)
+                        .on_input(move |value| TeamTotalsMessage::TeamDefinitionEdited(i, TeamDefinitionEdit::Team(value))),
+                    Button::new(Text::new("Remove")).on_press(TeamTotalsMessage::RemoveTeamDefinition(i)),
+                ].spacing(2),
+                text_input("Clubs separated by semicolons, e.g. *Boston*; Skating Club of New York", &self.team_definition_clubs[i])
+                    .on_input(move |value| TeamTotalsMessage::TeamDefinitionEdited(i, TeamDefinitionEdit::Clubs(value))),
+            ].spacing(2);
+
+            teams_column = teams_column.push(team_column).push(vertical_space().height(10));
+        }
+
+        let group_teams_by_region_checkbox = Checkbox::new("Clubs on No Team Are on the Team of Their Region", self.settings.group_teams_by_region).on_toggle(TeamTotalsMessage::GroupTeamsByRegion);
+        let add_team = Button::new(Text::new("Add Team").align_x(Alignment::Center)).on_press(TeamTotalsMessage::AddTeamDefinition).width(iced::Length::Fill);
+
+        teams_column
+            .push(group_teams_by_region_checkbox)
+            .push(vertical_space().height(5))
+            .push(add_team)
+    }
+
+    fn club_aliases_view(&self) -> widget::Column<'_, TeamTotalsMessage> {
+        let mut aliases_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Club Aliases"),
+            text("Results naming a club on the left are counted for the club on the right. Aliases are remembered for future competitions and apply the next time results are calculated."),
+            vertical_space().height(5),
+        ];
+
+        for (i, club_alias) in self.settings.club_aliases.aliases.iter().enumerate() {
+            aliases_column = aliases_column.push(row![
+                text_input("Name in results, e.g. SC of Boston", &club_alias.alias)
+                    .on_input(move |value| TeamTotalsMessage::ClubAliasEdited(i, ClubAliasEdit::Alias(value)))
+                    .width(iced::Length::Fill),
+                text_input("Club, e.g. Skating Club of Boston", &club_alias.club)
+                    .on_input(move |value| TeamTotalsMessage::ClubAliasEdited(i, ClubAliasEdit::Club(value)))
+                    .width(iced::Length::Fill),
+                Button::new(Text::new("Remove")).on_press(TeamTotalsMessage::RemoveClubAlias(i)),
+            ].spacing(2)).push(vertical_space().height(2));
+        }
+
+        aliases_column = aliases_column
+            .push(vertical_space().height(5))
+            .push(text("Suggest Merging Clubs With Names at Least This Alike (%)"))
+            .push(vertical_space().height(1))
+            .push(text_input("80", &self.club_match_threshold).on_input(TeamTotalsMessage::ClubMatchThreshold))
+            .push(vertical_space().height(5));
+
+        // Offer the clubs of the current results so a misspelled club can be mapped without retyping it.
+        let unaliased = self.club_points
+            .iter()
+            .map(|club_point| club_point.club().clone())
+            .filter(|club| self.settings.club_aliases.canonical(club).is_none())
+            .collect::<Vec<String>>();
+
+        let add_alias = Button::new(Text::new("Add Club Alias").align_x(Alignment::Center)).on_press(TeamTotalsMessage::AddClubAlias(String::new())).width(iced::Length::Fill);
+
+        if unaliased.is_empty() {
+            aliases_column.push(add_alias)
+        } else {
+            aliases_column
+                .push(pick_list(unaliased, None::<String>, TeamTotalsMessage::AddClubAlias).placeholder("Add Alias For a Club in the Results...").width(iced::Length::Fill))
+                .push(vertical_space().height(2))
+                .push(add_alias)
+        }
+    }
+
+    fn resort_club_points(&mut self) {
+        sort_results(&mut self.club_points, &self.settings.standings_tie_breakers);
+        self.update_edit_inputs();
+    }
+
+    fn tie_breakers_view(&self) -> widget::Column<'_, TeamTotalsMessage> {
+        let mut tie_breakers_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Tied Club Totals"),
+            text("Clubs the rules below cannot separate share a rank."),
+            vertical_space().height(1),
+        ];
+
+        for (i, tie_breaker) in self.settings.standings_tie_breakers.iter().enumerate() {
+            let mut raise = Button::new(Text::new("Up"));
+            if i > 0 {
+                raise = raise.on_press(TeamTotalsMessage::RaiseTieBreaker(i));
+            }
+
+            tie_breakers_column = tie_breakers_column.push(row![
+                text(format!("{}. {}", i + 1, tie_breaker.get_name())).align_y(Vertical::Center).height(30),
+                horizontal_space(),
+                raise,
+                horizontal_space().width(5),
+                Button::new(Text::new("Remove")).on_press(TeamTotalsMessage::RemoveTieBreaker(i)),
+            ]);
+        }
+
+        let unused = TieBreaker::ALL
+            .iter()
+            .filter(|tie_breaker| !self.settings.standings_tie_breakers.contains(tie_breaker))
+            .map(|tie_breaker| tie_breaker.get_name())
+            .collect::<Vec<String>>();
+
+        if !unused.is_empty() {
+            tie_breakers_column = tie_breakers_column
+                .push(vertical_space().height(5))
+                .push(pick_list(unused, None::<String>, TeamTotalsMessage::AddTieBreaker).placeholder("Add Tie-Breaker...").width(iced::Length::Fill));
+        }
+
+        tie_breakers_column
+    }
+
+    fn points_formula_view(&self) -> widget::Column<'_, TeamTotalsMessage> {
+        let feedback = match PointsFormula::parse(&self.settings.points_formula) {
+            Ok(formula) => {
+                // Show what the formula awards in a small field so mistakes are easy to spot.
+                let preview = (1..=FORMULA_PREVIEW_PARTICIPANTS)
+                    .map(|rank| formula.evaluate(rank, FORMULA_PREVIEW_PARTICIPANTS).map(|points| display_points(points, &self.settings).to_string()))
+                    .collect::<Result<Vec<String>, String>>();
+
+                match preview {
+                    Ok(preview) => text(format!("With {} participants: {}", FORMULA_PREVIEW_PARTICIPANTS, preview.join(", "))),
+                    Err(err) => text(err).style(text::danger),
+                }
+            }
+            Err(err) => text(err).style(text::danger),
+        };
+
+        column![
+            text("Points Formula"),
+            text("Use rank, participants, numbers, + - * / ^ and min, max, abs, floor, ceil or round."),
+            vertical_space().height(1),
+            text_input("e.g. max(0, participants - rank + 1)", &self.settings.points_formula).on_input(TeamTotalsMessage::PointsFormula),
+            vertical_space().height(1),
+            feedback,
+        ]
     }
 }
 
@@ -240,10 +524,21 @@ impl TeamTotalsGui {
             competition: String::new(),
             settings: settings.clone(),
             status: String::new(),
+            diagnostics: vec![],
             theme: Theme::Dark,
             font_size: settings.xlsx_font_size.to_string(),
+            minimum_field_size: settings.participant_quantity_exclusion_point.to_string(),
+            small_field_points_multiplier: settings.small_field_points_multiplier.to_string(),
+            max_scoring_entries_per_club: settings.max_scoring_entries_per_club.to_string(),
+            max_counted_results_per_skater: settings.max_counted_results_per_skater.to_string(),
+            participation_points: settings.participation_points.to_string(),
+            medal_table_places: settings.medal_table_places.to_string(),
+            club_match_threshold: settings.club_match_threshold.to_string(),
+            display_precision: settings.display_precision.to_string(),
             points_for_each_placement: vec![],
-            events: vec![],
+            points_rule_points: vec![],
+            team_definition_clubs: vec![],
+            opened_competition: None,
             event_controls: vec![],
             last_checkbox: -1,
             is_shift_down: false,
@@ -254,14 +549,13 @@ impl TeamTotalsGui {
             club_name_edits: vec![],
             club_points_60_edits: vec![],
             club_points_ijs_edits: vec![],
+            club_points_participation_edits: vec![],
 
             result_sets: vec![],
-
-            selected_scoring_system_type: Some(if settings.use_scoring_system_spreadsheet {
-                ScoringSystemChoice::Spreadsheet
-            } else {
-                ScoringSystemChoice::Single
-            }),
+            standings: None,
+            merge_suggestions: vec![],
+            unknown_clubs: vec![],
+            unmatched_skaters: vec![],
         };
 
         let mut tasks = vec![];
@@ -281,15 +575,20 @@ impl TeamTotalsGui {
         self.club_name_edits.clear();
         self.club_points_ijs_edits.clear();
         self.club_points_60_edits.clear();
+        self.club_points_participation_edits.clear();
 
         for (i, club_point) in self.club_points.iter().enumerate() {
             self.club_name_edits.push(ClubPointsField::new(i, club_point.club().clone(), Some(iced::Length::FillPortion(3))));
             self.club_points_ijs_edits.push(ClubPointsField::new(i, match club_point.points_ijs() {
-                Some(value) => { format!("{}", value) }
+                Some(value) => { format!("{}", display_points(value, &self.settings)) }
                 None => { String::new() }
             }, None));
             self.club_points_60_edits.push(ClubPointsField::new(i, match club_point.points_60() {
-                Some(value) => { format!("{}", value) }
+                Some(value) => { format!("{}", display_points(value, &self.settings)) }
+                None => { String::new() }
+            }, None));
+            self.club_points_participation_edits.push(ClubPointsField::new(i, match club_point.points_participation() {
+                Some(value) => { format!("{}", display_points(value, &self.settings)) }
                 None => { String::new() }
             }, None));
         }
@@ -309,16 +608,27 @@ impl TeamTotalsGui {
                 let settings = self.settings.clone();
 
                 Task::perform(async move {
-                    retrieve_events(competition, settings)
+                    Competition::open(&competition, &settings)
                 }, TeamTotalsMessage::EventsRetrieved)
             }
 
-            TeamTotalsMessage::EventsRetrieved((events, status)) => {
-                self.events = events;
-                self.event_controls = self.events.iter().enumerate().map(|(i, event)| {
-                    EventCheckbox::new(i, event.clone())
-                }).collect::<Vec<EventCheckbox>>();
-                self.status = status;
+            TeamTotalsMessage::EventsRetrieved(competition) => {
+                match competition {
+                    Ok(competition) => {
+                        self.event_controls = competition.events().iter().enumerate().map(|(i, event)| {
+                            EventCheckbox::new(i, event.clone())
+                        }).collect::<Vec<EventCheckbox>>();
+                        self.status = competition.status().clone();
+                        self.diagnostics = competition.diagnostics().clone();
+                        self.opened_competition = Some(competition);
+                    }
+                    Err(status) => {
+                        self.event_controls.clear();
+                        self.status = status;
+                        self.diagnostics.clear();
+                        self.opened_competition = None;
+                    }
+                }
                 //self.start_status_timer();
 
                 Task::none()
@@ -358,6 +668,16 @@ impl TeamTotalsGui {
                 settings_changed = true;
                 Task::none()
             }
+            TeamTotalsMessage::GroupTeamsByRegion(group_teams_by_region) => {
+                self.settings.group_teams_by_region = group_teams_by_region;
+                settings_changed = true;
+                Task::none()
+            }
+            TeamTotalsMessage::ShowDisciplineSubtotals(show_discipline_subtotals) => {
+                self.settings.show_discipline_subtotals = show_discipline_subtotals;
+                settings_changed = true;
+                Task::none()
+            }
             TeamTotalsMessage::XLSXFontSize(font_size) => {
                 match font_size.parse::<u32>() {
                     Ok(value) => {
@@ -370,6 +690,98 @@ impl TeamTotalsGui {
 
                 Task::none()
             }
+            TeamTotalsMessage::MinimumFieldSize(minimum_field_size) => {
+                if let Ok(value) = minimum_field_size.parse::<u64>() {
+                    self.minimum_field_size = minimum_field_size;
+                    self.settings.participant_quantity_exclusion_point = value;
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::SmallFieldPointsMultiplier(small_field_points_multiplier) => {
+                if let Ok(value) = small_field_points_multiplier.parse::<Decimal>() {
+                    self.small_field_points_multiplier = small_field_points_multiplier;
+                    self.settings.small_field_points_multiplier = value;
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::PointsFormula(points_formula) => {
+                self.settings.points_formula = points_formula;
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::MaxScoringEntriesPerClub(max_scoring_entries_per_club) => {
+                if let Ok(value) = max_scoring_entries_per_club.parse::<u64>() {
+                    self.max_scoring_entries_per_club = max_scoring_entries_per_club;
+                    self.settings.max_scoring_entries_per_club = value;
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::MaxCountedResultsPerSkater(max_counted_results_per_skater) => {
+                if let Ok(value) = max_counted_results_per_skater.parse::<u64>() {
+                    self.max_counted_results_per_skater = max_counted_results_per_skater;
+                    self.settings.max_counted_results_per_skater = value;
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::ParticipationPoints(participation_points) => {
+                if let Ok(value) = participation_points.parse::<Decimal>() {
+                    self.participation_points = participation_points;
+                    self.settings.participation_points = value;
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::ParticipationBasisSelected(participation_basis) => {
+                self.settings.participation_basis = participation_basis;
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::StandingsModeSelected(standings_mode) => {
+                self.settings.standings_mode = standings_mode;
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::ClubMatchThreshold(club_match_threshold) => {
+                if let Ok(value) = club_match_threshold.parse::<u64>() {
+                    self.club_match_threshold = club_match_threshold;
+                    self.settings.club_match_threshold = value;
+                    self.merge_suggestions = merge_suggestions(&self.club_points, &self.settings);
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::MedalTablePlaces(medal_table_places) => {
+                if let Ok(value) = medal_table_places.parse::<u64>() {
+                    self.medal_table_places = medal_table_places;
+                    self.settings.medal_table_places = value;
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::DisplayPrecision(display_precision) => {
+                if let Ok(value) = display_precision.parse::<u32>() {
+                    self.display_precision = display_precision;
+                    self.settings.display_precision = value;
+                    self.update_edit_inputs();
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
             TeamTotalsMessage::ISUCalcBaseDirectory(isu_calc_base_directory) => {
                 self.settings.isu_calc_base_directory = isu_calc_base_directory;
                 settings_changed = true;
@@ -430,7 +842,7 @@ impl TeamTotalsGui {
                     }
 
                     ClubPointsEdit::Submitted => {
-                        sort_results(&mut self.club_points);
+                        sort_results(&mut self.club_points, &self.settings.standings_tie_breakers);
                         self.update_edit_inputs();
                     }
                 }
@@ -444,7 +856,7 @@ impl TeamTotalsGui {
                     ClubPointsEdit::Edited(value) => {
                         match self.club_points.get_mut(i) {
                             Some(club_point) => {
-                                match value.parse::<f64>() {
+                                match value.parse::<Decimal>() {
                                     Ok(points) => {
                                         club_point.set_points_ijs(points);
                                         self.update_edit_inputs();
@@ -453,7 +865,7 @@ impl TeamTotalsGui {
                                         if value.trim().is_empty() {
                                             club_point.set_points_ijs_none();
                                         } else {
-                                            club_point.set_points_ijs(last_value.unwrap_or(0.0));
+                                            club_point.set_points_ijs(last_value.unwrap_or_default());
                                         }
                                         self.update_edit_inputs();
                                     }
@@ -465,9 +877,9 @@ impl TeamTotalsGui {
 
                     ClubPointsEdit::Submitted => {
                         if self.club_points.get(i).unwrap().points_ijs().is_none() {
-                            self.club_points.get_mut(i).unwrap().set_points_ijs(0.0);
+                            self.club_points.get_mut(i).unwrap().set_points_ijs(Decimal::ZERO);
                         }
-                        sort_results(&mut self.club_points);
+                        sort_results(&mut self.club_points, &self.settings.standings_tie_breakers);
                         self.update_edit_inputs();
                     }
                 }
@@ -481,7 +893,7 @@ impl TeamTotalsGui {
                     ClubPointsEdit::Edited(value) => {
                         match self.club_points.get_mut(i) {
                             Some(club_point) => {
-                                match value.parse::<f64>() {
+                                match value.parse::<Decimal>() {
                                     Ok(points) => {
                                         club_point.set_points_60(points);
                                         self.update_edit_inputs();
@@ -490,7 +902,7 @@ impl TeamTotalsGui {
                                         if value.trim().is_empty() {
                                             club_point.set_points_60_none();
                                         } else {
-                                            club_point.set_points_60(last_value.unwrap_or(0.0));
+                                            club_point.set_points_60(last_value.unwrap_or_default());
                                         }
                                         self.update_edit_inputs();
                                     }
@@ -502,9 +914,42 @@ impl TeamTotalsGui {
 
                     ClubPointsEdit::Submitted => {
                         if self.club_points.get(i).unwrap().points_60().is_none() {
-                            self.club_points.get_mut(i).unwrap().set_points_60(0.0);
+                            self.club_points.get_mut(i).unwrap().set_points_60(Decimal::ZERO);
                         }
-                        sort_results(&mut self.club_points);
+                        sort_results(&mut self.club_points, &self.settings.standings_tie_breakers);
+                        self.update_edit_inputs();
+                    }
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::ClubPointsParticipationEdited(i, value) => {
+                let last_value = self.club_points.get(i).unwrap().points_participation();
+                match value {
+                    ClubPointsEdit::Edited(value) => {
+                        if let Some(club_point) = self.club_points.get_mut(i) {
+                            match value.parse::<Decimal>() {
+                                Ok(points) => {
+                                    club_point.set_points_participation(points);
+                                    self.update_edit_inputs();
+                                }
+                                Err(_) => {
+                                    if value.trim().is_empty() {
+                                        club_point.set_points_participation_none();
+                                    } else {
+                                        club_point.set_points_participation(last_value.unwrap_or_default());
+                                    }
+                                    self.update_edit_inputs();
+                                }
+                            }
+                        }
+                    }
+
+                    ClubPointsEdit::Submitted => {
+                        if self.club_points.get(i).unwrap().points_participation().is_none() {
+                            self.club_points.get_mut(i).unwrap().set_points_participation(Decimal::ZERO);
+                        }
+                        sort_results(&mut self.club_points, &self.settings.standings_tie_breakers);
                         self.update_edit_inputs();
                     }
                 }
@@ -558,12 +1003,16 @@ impl TeamTotalsGui {
                                 i as isize..self.last_checkbox + 1
                             } {
                                 let x = x as usize;
-                                self.event_controls[x].event.active = b;
-                                self.events[x] = self.event_controls[x].event.clone();
+                                self.event_controls[x].event.set_active(b);
+                                if let Some(competition) = &mut self.opened_competition {
+                                    competition.events_mut()[x] = self.event_controls[x].event.clone();
+                                }
                             }
                         } else {
-                            self.event_controls[i].event.active = b;
-                            self.events[i] = self.event_controls[i].event.clone();
+                            self.event_controls[i].event.set_active(b);
+                            if let Some(competition) = &mut self.opened_competition {
+                                competition.events_mut()[i] = self.event_controls[i].event.clone();
+                            }
                         }
 
                         self.last_checkbox = i as isize;
@@ -573,19 +1022,31 @@ impl TeamTotalsGui {
                 Task::none()
             }
             TeamTotalsMessage::CalculateResults => {
-                let events = self.events.clone();
-                let settings = self.settings.clone();
+                match self.opened_competition.clone() {
+                    Some(competition) => {
+                        let rules = Rules::from(&self.settings);
 
-                Task::perform(async move {
-                    let (club_points, result_sets, result, _status) = calculate(events, &settings);
-                    (club_points, result_sets, result)
-                }, TeamTotalsMessage::ResultsRetrieved)
+                        Task::perform(async move {
+                            competition.tabulate(&rules)
+                        }, TeamTotalsMessage::ResultsRetrieved)
+                    }
+                    None => Task::none(),
+                }
             }
 
-            TeamTotalsMessage::ResultsRetrieved((club_points, result_sets, status)) => {
-                self.status = status;
-                self.club_points = club_points;
-                self.result_sets = result_sets;
+            TeamTotalsMessage::ResultsRetrieved(standings) => {
+                self.status = standings.status().clone();
+                self.diagnostics = match &self.opened_competition {
+                    Some(competition) => competition.diagnostics().clone(),
+                    None => vec![],
+                };
+                self.diagnostics.extend(standings.diagnostics().clone());
+                self.club_points = standings.club_points().clone();
+                self.result_sets = standings.result_sets().clone();
+                self.merge_suggestions = merge_suggestions(&self.club_points, &self.settings);
+                self.unknown_clubs = unknown_clubs(&self.club_points, &self.settings);
+                self.unmatched_skaters = unmatched_skaters(&self.result_sets);
+                self.standings = Some(standings);
 
                 self.update_edit_inputs();
 
@@ -627,7 +1088,7 @@ impl TeamTotalsGui {
                 Task::none()
             }
             TeamTotalsMessage::FindSpreadsheetDirectory => {
-                file_utils::check_and_create_dir(&self.settings.output_directory);
+                check_and_create_dir(&self.settings.output_directory);
                 let scoring_system_directory = self.settings.scoring_system_file_name.clone();
 
                 match scoring_system_directory {
@@ -657,7 +1118,7 @@ impl TeamTotalsGui {
                 match directory {
                     Some(directory) => {
                         let scoring_system_file_name = Some(directory.to_str().unwrap_or("").to_string().replace("\\", "/"));
-                        match scoring_system_reader::read_scoring_system_spreadsheet(scoring_system_file_name.clone()) {
+                        match read_scoring_system_spreadsheet(scoring_system_file_name.clone()) {
                             Ok(_) => {
                                 self.settings.scoring_system_file_name = scoring_system_file_name;
                                 self.status = "Successfully read selected scoring system spreadsheet".to_string();
@@ -678,8 +1139,100 @@ impl TeamTotalsGui {
 
                 Task::none()
             }
+            TeamTotalsMessage::FindRoster => {
+                match self.settings.roster_file_name {
+                    Some(_) => {
+                        Task::perform(async move {
+                            None
+                        }, TeamTotalsMessage::FindRosterReceived)
+                    },
+                    None => {
+                        let output_directory = self.settings.output_directory.clone();
+
+                        Task::perform(async move {
+                            FileDialogBuilder::default()
+                                .set_location(output_directory.as_str())
+                                .add_filter("Skater Roster", ["csv", "xlsx", "xls", "ods"])
+                                .open_single_file()
+                                .show()
+                                .unwrap_or_default()
+                        }, TeamTotalsMessage::FindRosterReceived)
+                    }
+                }
+            }
+            TeamTotalsMessage::FindRosterReceived(roster) => {
+                match roster {
+                    Some(roster) => {
+                        let roster_file_name = roster.to_str().unwrap_or("").to_string().replace("\\", "/");
+                        match Roster::read(&roster_file_name) {
+                            Ok(roster) => {
+                                self.settings.roster_file_name = Some(roster_file_name);
+                                self.status = format!("Read {} skater(s) from the selected skater roster", roster.entries().len());
+                            },
+                            Err(err) => {
+                                self.status = err;
+                                self.settings.roster_file_name = None;
+                            },
+                        }
+                    }
+                    None => {
+                        self.status = "No skater roster selected".to_string();
+                        self.settings.roster_file_name = None;
+                    }
+                }
+                self.unmatched_skaters.clear();
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::FindClubDirectory => {
+                match self.settings.club_directory_file_name {
+                    Some(_) => {
+                        Task::perform(async move {
+                            None
+                        }, TeamTotalsMessage::FindClubDirectoryReceived)
+                    },
+                    None => {
+                        let output_directory = self.settings.output_directory.clone();
+
+                        Task::perform(async move {
+                            FileDialogBuilder::default()
+                                .set_location(output_directory.as_str())
+                                .add_filter("Club Directory", ["csv", "xlsx", "xls", "ods"])
+                                .open_single_file()
+                                .show()
+                                .unwrap_or_default()
+                        }, TeamTotalsMessage::FindClubDirectoryReceived)
+                    }
+                }
+            }
+            TeamTotalsMessage::FindClubDirectoryReceived(directory) => {
+                match directory {
+                    Some(directory) => {
+                        let club_directory_file_name = directory.to_str().unwrap_or("").to_string().replace("\\", "/");
+                        match ClubDirectory::read(&club_directory_file_name) {
+                            Ok(club_directory) => {
+                                self.settings.club_directory_file_name = Some(club_directory_file_name);
+                                self.status = format!("Read {} club(s) from the selected club directory", club_directory.clubs().len());
+                            },
+                            Err(err) => {
+                                self.status = err;
+                                self.settings.club_directory_file_name = None;
+                            },
+                        }
+                    }
+                    None => {
+                        self.status = "No club directory selected".to_string();
+                        self.settings.club_directory_file_name = None;
+                    }
+                }
+                self.unknown_clubs.clear();
+                settings_changed = true;
+
+                Task::none()
+            }
             TeamTotalsMessage::OpenInFileViewer => {
-                file_utils::check_and_create_dir(&self.settings.output_directory);
+                check_and_create_dir(&self.settings.output_directory);
                 println!("{}", &self.settings.output_directory);
                 let directory = self.settings.output_directory.clone();
                 thread::spawn(move || {
@@ -711,8 +1264,10 @@ impl TeamTotalsGui {
                 Task::none()
             }
             TeamTotalsMessage::OutputResults => {
-                if self.club_points.len() > 0 {
-                    file_utils::output_files(&self.club_points, &self.result_sets, &self.settings, &self.competition);
+                if let Some(standings) = self.standings.as_mut().filter(|_| self.club_points.len() > 0) {
+                    // Any totals corrected by hand in the edit view are written in place of the calculated ones.
+                    standings.set_club_points(self.club_points.clone());
+                    standings.output_files(&self.settings, &self.competition);
                     self.status = String::from("Success! Press \"Open Output Directory...\" to view generated files");
                 } else {
                     self.status = String::from("No results available");
@@ -723,14 +1278,212 @@ impl TeamTotalsGui {
             TeamTotalsMessage::NoneInput(_) => {
                 Task::none()
             }
-            TeamTotalsMessage::ScoringSystemSelected(choice) => {
-                self.selected_scoring_system_type = Some(choice);
+            TeamTotalsMessage::PointsSystemSelected(points_system) => {
+                self.settings.points_system = points_system;
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::ChartResolutionSelected(chart_resolution) => {
+                self.settings.chart_resolution = chart_resolution;
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::TiePolicySelected(tie_policy) => {
+                self.settings.tie_policy = tie_policy;
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::AddTieBreaker(name) => {
+                if let Some(tie_breaker) = TieBreaker::ALL.into_iter().find(|tie_breaker| tie_breaker.get_name() == name) {
+                    self.settings.standings_tie_breakers.push(tie_breaker);
+                    self.resort_club_points();
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::RemoveTieBreaker(i) => {
+                if i < self.settings.standings_tie_breakers.len() {
+                    self.settings.standings_tie_breakers.remove(i);
+                    self.resort_club_points();
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::RaiseTieBreaker(i) => {
+                if i > 0 && i < self.settings.standings_tie_breakers.len() {
+                    self.settings.standings_tie_breakers.swap(i - 1, i);
+                    self.resort_club_points();
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::CrossClubCreditSelected(cross_club_credit) => {
+                self.settings.cross_club_credit = cross_club_credit;
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::IjsResultsCountingSelected(ijs_results_counting) => {
+                self.settings.ijs_results_counting = ijs_results_counting;
+                settings_changed = true;
+
+                // The events to show depend on this setting, so read them again.
+                if self.opened_competition.is_some() {
+                    Task::done(TeamTotalsMessage::RetrieveResults)
+                } else {
+                    Task::none()
+                }
+            }
+            TeamTotalsMessage::RoundingModeSelected(rounding_mode) => {
+                self.settings.rounding_mode = rounding_mode;
+                self.update_edit_inputs();
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::AddPointsRule => {
+                self.settings.points_rules.push(PointsRule::default());
+                self.synchronize_gui_with_settings();
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::RemovePointsRule(i) => {
+                if i < self.settings.points_rules.len() {
+                    self.settings.points_rules.remove(i);
+                    self.synchronize_gui_with_settings();
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::AddTeamDefinition => {
+                self.settings.team_definitions.push(TeamDefinition::default());
+                self.synchronize_gui_with_settings();
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::RemoveTeamDefinition(i) => {
+                if i < self.settings.team_definitions.len() {
+                    self.settings.team_definitions.remove(i);
+                    self.synchronize_gui_with_settings();
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::TeamDefinitionEdited(i, edit) => {
+                if let Some(definition) = self.settings.team_definitions.get_mut(i) {
+                    match edit {
+                        TeamDefinitionEdit::Team(team) => definition.team = team,
+                        TeamDefinitionEdit::Clubs(clubs) => {
+                            // Keep what was typed, separators included, while the list is edited.
+                            definition.clubs = clubs
+                                .split(';')
+                                .map(|club| club.trim().to_string())
+                                .filter(|club| !club.is_empty())
+                                .collect();
+                            self.team_definition_clubs[i] = clubs;
+                        }
+                    }
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::AcceptMergeSuggestion(i) => {
+                match self.merge_suggestions.get(i) {
+                    Some(suggestion) => {
+                        self.settings.club_aliases.set(suggestion.club(), suggestion.target());
+                        settings_changed = true;
+
+                        // Calculate again so that the alias applies to every result of the club.
+                        Task::done(TeamTotalsMessage::CalculateResults)
+                    }
+                    None => Task::none(),
+                }
+            }
+            TeamTotalsMessage::RejectMergeSuggestion(i) => {
+                if let Some(suggestion) = self.merge_suggestions.get(i) {
+                    self.settings.club_aliases.reject(suggestion.club(), suggestion.target());
+                    self.merge_suggestions = merge_suggestions(&self.club_points, &self.settings);
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::AddClubAlias(alias) => {
+                self.settings.club_aliases.aliases.push(ClubAlias { alias, club: String::new() });
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::RemoveClubAlias(i) => {
+                if i < self.settings.club_aliases.aliases.len() {
+                    self.settings.club_aliases.aliases.remove(i);
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::ClubAliasEdited(i, edit) => {
+                if let Some(club_alias) = self.settings.club_aliases.aliases.get_mut(i) {
+                    match edit {
+                        ClubAliasEdit::Alias(alias) => club_alias.alias = alias,
+                        ClubAliasEdit::Club(club) => club_alias.club = club,
+                    }
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::AddDisciplineMapping => {
+                self.settings.discipline_mappings.push(DisciplineMapping::default());
+                settings_changed = true;
 
-                match choice {
-                    ScoringSystemChoice::Single => { self.settings.use_scoring_system_spreadsheet = false }
-                    ScoringSystemChoice::Spreadsheet => { self.settings.use_scoring_system_spreadsheet = true }
+                Task::none()
+            }
+            TeamTotalsMessage::RemoveDisciplineMapping(i) => {
+                if i < self.settings.discipline_mappings.len() {
+                    self.settings.discipline_mappings.remove(i);
+                    settings_changed = true;
+                }
+
+                Task::none()
+            }
+            TeamTotalsMessage::DisciplineMappingEdited(i, edit) => {
+                if let Some(mapping) = self.settings.discipline_mappings.get_mut(i) {
+                    match edit {
+                        DisciplineMappingEdit::EventPattern(event_pattern) => mapping.event_pattern = event_pattern,
+                        DisciplineMappingEdit::Discipline(name) => {
+                            if let Some(discipline) = Discipline::ALL.into_iter().find(|discipline| discipline.get_name() == name) {
+                                mapping.discipline = discipline;
+                            }
+                        }
+                    }
+                    settings_changed = true;
                 }
 
+                Task::none()
+            }
+            TeamTotalsMessage::PointsRuleEdited(i, edit) => {
+                self.apply_points_rule_edit(i, edit);
+                settings_changed = true;
+
+                Task::none()
+            }
+            TeamTotalsMessage::FieldSizeStatusToggled(status, counted) => {
+                self.settings.statuses_counted_in_field_size.retain(|s| *s != status);
+                if counted {
+                    self.settings.statuses_counted_in_field_size.push(status);
+                }
                 settings_changed = true;
 
                 Task::none()
@@ -758,10 +1511,12 @@ impl TeamTotalsGui {
 
         let retrieve_data_button = Button::new(Text::new("Retrieve Data").align_x(Alignment::Center)).on_press(TeamTotalsMessage::RetrieveResults).width(140);
         let mut can_calculate = false;
-        for event in &self.events {
-            if event.active {
-                can_calculate = true;
-                break;
+        if let Some(competition) = &self.opened_competition {
+            for event in competition.events() {
+                if event.active() {
+                    can_calculate = true;
+                    break;
+                }
             }
         }
         let calculate_button = if can_calculate { Button::new(Text::new("Tabulate Results").align_x(Alignment::Center)).on_press(TeamTotalsMessage::CalculateResults).width(140) } else { Button::new(Text::new("Tabulate Results").align_x(Alignment::Center)).width(140) };
@@ -784,7 +1539,63 @@ impl TeamTotalsGui {
 
         let open_output_directory_button = Button::new(Text::new("Open Output Directory").align_x(Alignment::Center)).on_press(TeamTotalsMessage::OpenInFileViewer).width(290);
         let open_output_directory_row = row![open_output_directory_button, horizontal_space().width(10), text(&self.status)];
-        let column1 = column![ competition_input_row, vertical_space().height(10), calculate_button_row, vertical_space().height(10), open_output_directory_row, vertical_space().height(10), iced::widget::horizontal_rule(1), vertical_space().height(10), loaded_events_scrollable ].padding(10).width(iced::Length::FillPortion(5));
+        let mut column1 = column![ competition_input_row, vertical_space().height(10), calculate_button_row, vertical_space().height(10), open_output_directory_row, vertical_space().height(10) ];
+
+        // Show the operator every file or event that had to be skipped.
+        if !self.diagnostics.is_empty() {
+            let diagnostics_column = Column::with_children(self.diagnostics.iter().map(|diagnostic| {
+                text(diagnostic.to_string()).style(text::danger).into()
+            })).spacing(5);
+            column1 = column1
+                .push(text(format!("{} problem(s) found while reading results:", self.diagnostics.len())))
+                .push(vertical_space().height(5))
+                .push(scrollable(diagnostics_column).height(iced::Length::Fixed(DIAGNOSTICS_HEIGHT)).width(iced::Length::Fill))
+                .push(vertical_space().height(10));
+        }
+
+        // List the skaters whose club the roster could not settle, so they can be resolved by hand.
+        if !self.unmatched_skaters.is_empty() {
+            let unmatched_skaters_column = Column::with_children(self.unmatched_skaters.iter().map(|skater| {
+                text(skater).style(text::danger).into()
+            })).spacing(5);
+            column1 = column1
+                .push(text(format!("{} skater(s) could not be matched to the skater roster:", self.unmatched_skaters.len())))
+                .push(vertical_space().height(5))
+                .push(scrollable(unmatched_skaters_column).height(iced::Length::Fixed(DIAGNOSTICS_HEIGHT)).width(iced::Length::Fill))
+                .push(vertical_space().height(10));
+        }
+
+        // Flag clubs the club directory does not list, so they can be checked before output.
+        if !self.unknown_clubs.is_empty() {
+            let unknown_clubs_column = Column::with_children(self.unknown_clubs.iter().map(|club| {
+                text(club).style(text::danger).into()
+            })).spacing(5);
+            column1 = column1
+                .push(text(format!("{} club(s) are not in the club directory. Check them before output:", self.unknown_clubs.len())))
+                .push(vertical_space().height(5))
+                .push(scrollable(unknown_clubs_column).height(iced::Length::Fixed(DIAGNOSTICS_HEIGHT)).width(iced::Length::Fill))
+                .push(vertical_space().height(10));
+        }
+
+        // Let the operator decide whether clubs with similar names are the same club.
+        if !self.merge_suggestions.is_empty() {
+            let suggestions_column = Column::with_children(self.merge_suggestions.iter().enumerate().map(|(i, suggestion)| {
+                row![
+                    text(format!("\"{}\" may be \"{}\" ({}% match)", suggestion.club(), suggestion.target(), suggestion.confidence())).align_y(Vertical::Center).height(30),
+                    horizontal_space(),
+                    Button::new(Text::new("Merge")).on_press(TeamTotalsMessage::AcceptMergeSuggestion(i)),
+                    horizontal_space().width(5),
+                    Button::new(Text::new("Keep Separate")).on_press(TeamTotalsMessage::RejectMergeSuggestion(i)),
+                ].into()
+            })).spacing(5);
+            column1 = column1
+                .push(text(format!("{} club(s) may be listed under more than one name:", self.merge_suggestions.len())))
+                .push(vertical_space().height(5))
+                .push(scrollable(suggestions_column).height(iced::Length::Fixed(DIAGNOSTICS_HEIGHT)).width(iced::Length::Fill))
+                .push(vertical_space().height(10));
+        }
+
+        let column1 = column1.push(iced::widget::horizontal_rule(1)).push(vertical_space().height(10)).push(loaded_events_scrollable).padding(10).width(iced::Length::FillPortion(5));
 
         let include_60_checkbox = Checkbox::new("Include 6.0", self.settings.include_60).on_toggle(TeamTotalsMessage::Include60);
         let include_ijs_checkbox = Checkbox::new("Include IJS", self.settings.include_ijs).on_toggle(TeamTotalsMessage::IncludeIJS);
@@ -792,6 +1603,7 @@ impl TeamTotalsGui {
         let generate_xlsx_info_dump_checkbox = Checkbox::new("Generate .xlsx Info Dump File", self.settings.generate_xlsx_info_dump).on_toggle(TeamTotalsMessage::GenerateXLSXInfoDump);
         let generate_html_checkbox = Checkbox::new("Generate .html File", self.settings.generate_html).on_toggle(TeamTotalsMessage::GenerateHTML);
         let attempt_60_club_correction_checkbox = Checkbox::new("Attempt 6.0 Club Correction", self.settings.attempt_automatic_60_club_name_recombination).on_toggle(TeamTotalsMessage::Attempt60ClubCorrection);
+        let show_discipline_subtotals_checkbox = Checkbox::new("Show Discipline Subtotals", self.settings.show_discipline_subtotals).on_toggle(TeamTotalsMessage::ShowDisciplineSubtotals);
         let use_event_name_checkbox = Checkbox::new("Use Event Name for Results Path", self.settings.use_event_name_for_results_path).on_toggle(TeamTotalsMessage::UseEventNameForResultsPath);
 
         let font_size = text_input("", &self.font_size).on_input(TeamTotalsMessage::XLSXFontSize);
@@ -821,6 +1633,8 @@ impl TeamTotalsGui {
                 vertical_space().height(10),
                 generate_html_checkbox,
                 vertical_space().height(10),
+                show_discipline_subtotals_checkbox,
+                vertical_space().height(10),
                 attempt_60_club_correction_checkbox,
                 vertical_space().height(10),
                 use_event_name_checkbox,
@@ -840,13 +1654,168 @@ impl TeamTotalsGui {
             ].padding(10);
 
         let scoring_system_column: widget::Column<'_, TeamTotalsMessage> = column![
-            radio("Use Single Scoring System", ScoringSystemChoice::Single, self.selected_scoring_system_type, TeamTotalsMessage::ScoringSystemSelected),
-            radio("Use Scoring System Spreadsheet", ScoringSystemChoice::Spreadsheet, self.selected_scoring_system_type, TeamTotalsMessage::ScoringSystemSelected),
+            radio("Use Single Scoring System", PointsSystemKind::FlatTable, Some(self.settings.points_system), TeamTotalsMessage::PointsSystemSelected),
+            radio("Use Scoring System Spreadsheet", PointsSystemKind::FieldSizeChart, Some(self.settings.points_system), TeamTotalsMessage::PointsSystemSelected),
+            radio("Use Event Rules", PointsSystemKind::EventRules, Some(self.settings.points_system), TeamTotalsMessage::PointsSystemSelected),
+            radio("Use Points Formula", PointsSystemKind::Formula, Some(self.settings.points_system), TeamTotalsMessage::PointsSystemSelected),
         ].into();
 
         column2 = column2.push(scoring_system_column).push(vertical_space().height(10));
 
-        if self.settings.use_scoring_system_spreadsheet {
+        let ijs_results_counting_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("IJS Results"),
+            vertical_space().height(1),
+            radio("Count Final Results Only", IjsResultsCounting::FinalResultsOnly, Some(self.settings.ijs_results_counting), TeamTotalsMessage::IjsResultsCountingSelected),
+            radio("Count Each Segment", IjsResultsCounting::PerSegment, Some(self.settings.ijs_results_counting), TeamTotalsMessage::IjsResultsCountingSelected),
+        ];
+
+        column2 = column2.push(ijs_results_counting_column).push(vertical_space().height(10));
+
+        let tie_policy_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Tied Placements"),
+            vertical_space().height(1),
+            radio("Average Points of the Tied Places", TiePolicy::Average, Some(self.settings.tie_policy), TeamTotalsMessage::TiePolicySelected),
+            radio("Award Points of the Higher Place", TiePolicy::Higher, Some(self.settings.tie_policy), TeamTotalsMessage::TiePolicySelected),
+            radio("Award Points of the Lower Place", TiePolicy::Lower, Some(self.settings.tie_policy), TeamTotalsMessage::TiePolicySelected),
+        ];
+
+        column2 = column2.push(tie_policy_column).push(vertical_space().height(10));
+
+        column2 = column2.push(self.tie_breakers_view()).push(vertical_space().height(10));
+
+        let use_club_directory: Button<TeamTotalsMessage> = Button::new(
+            Text::new(
+                if self.settings.club_directory_file_name.is_none() {
+                    "Use Club Directory..."
+                } else {
+                    "Remove Club Directory"
+                })).on_press(TeamTotalsMessage::FindClubDirectory);
+
+        let use_roster: Button<TeamTotalsMessage> = Button::new(
+            Text::new(
+                if self.settings.roster_file_name.is_none() {
+                    "Use Skater Roster..."
+                } else {
+                    "Remove Skater Roster"
+                })).on_press(TeamTotalsMessage::FindRoster);
+
+        column2 = column2.push(use_roster).push(vertical_space().height(10));
+        column2 = column2.push(use_club_directory).push(vertical_space().height(10));
+        column2 = column2.push(self.club_aliases_view()).push(vertical_space().height(10));
+        column2 = column2.push(self.team_definitions_view()).push(vertical_space().height(10));
+
+        if self.settings.show_discipline_subtotals || !self.settings.discipline_mappings.is_empty() {
+            column2 = column2.push(self.discipline_mappings_view()).push(vertical_space().height(10));
+        }
+
+        let mut standings_mode_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Standings"),
+            vertical_space().height(1),
+            radio("Rank Clubs by Points", StandingsMode::Points, Some(self.settings.standings_mode), TeamTotalsMessage::StandingsModeSelected),
+            radio("Rank Clubs by Points, With a Medal Table", StandingsMode::PointsAndMedals, Some(self.settings.standings_mode), TeamTotalsMessage::StandingsModeSelected),
+            radio("Rank Clubs by Medals", StandingsMode::Medals, Some(self.settings.standings_mode), TeamTotalsMessage::StandingsModeSelected),
+        ];
+
+        if self.settings.standings_mode.includes_medals() {
+            standings_mode_column = standings_mode_column
+                .push(vertical_space().height(5))
+                .push(text("Places Counted in the Medal Table (3 to 6)"))
+                .push(vertical_space().height(1))
+                .push(text_input("3", &self.medal_table_places).on_input(TeamTotalsMessage::MedalTablePlaces));
+        }
+
+        column2 = column2.push(standings_mode_column).push(vertical_space().height(10));
+
+        let mut field_size_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Count in Field Size"),
+            vertical_space().height(1),
+        ];
+
+        for status in [CompetitorStatus::Placed, CompetitorStatus::Withdrew, CompetitorStatus::DidNotStart, CompetitorStatus::Disqualified, CompetitorStatus::NoRank] {
+            let counted = self.settings.statuses_counted_in_field_size.contains(&status);
+            field_size_column = field_size_column.push(
+                Checkbox::new(status.get_name(), counted).on_toggle(move |counted| TeamTotalsMessage::FieldSizeStatusToggled(status, counted))
+            );
+        }
+
+        column2 = column2.push(field_size_column).push(vertical_space().height(10));
+
+        let minimum_field_size = text_input("0 to award points in every event", &self.minimum_field_size).on_input(TeamTotalsMessage::MinimumFieldSize);
+        let small_field_points_multiplier = text_input("0 to award no points", &self.small_field_points_multiplier).on_input(TeamTotalsMessage::SmallFieldPointsMultiplier);
+        let minimum_field_size_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Minimum Field Size"),
+            vertical_space().height(1),
+            minimum_field_size,
+            vertical_space().height(5),
+            text("Points Multiplier Below Minimum"),
+            vertical_space().height(1),
+            small_field_points_multiplier,
+        ];
+
+        column2 = column2.push(minimum_field_size_column).push(vertical_space().height(10));
+
+        let max_scoring_entries_per_club = text_input("0 to count every entry", &self.max_scoring_entries_per_club).on_input(TeamTotalsMessage::MaxScoringEntriesPerClub);
+        let max_scoring_entries_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Scoring Entries per Club in Each Event"),
+            vertical_space().height(1),
+            max_scoring_entries_per_club,
+            vertical_space().height(5),
+            text("Counted Results per Skater"),
+            vertical_space().height(1),
+            text_input("0 to count every result", &self.max_counted_results_per_skater).on_input(TeamTotalsMessage::MaxCountedResultsPerSkater),
+        ];
+
+        column2 = column2.push(max_scoring_entries_column).push(vertical_space().height(10));
+
+        let participation_points = text_input("0 to award none", &self.participation_points).on_input(TeamTotalsMessage::ParticipationPoints);
+        let participation_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Participation Points"),
+            vertical_space().height(1),
+            participation_points,
+            vertical_space().height(5),
+            radio("For Every Entry", ParticipationBasis::Entry, Some(self.settings.participation_basis), TeamTotalsMessage::ParticipationBasisSelected),
+            radio("Once for Every Skater", ParticipationBasis::Skater, Some(self.settings.participation_basis), TeamTotalsMessage::ParticipationBasisSelected),
+            radio("Once for Every Skater Who Placed", ParticipationBasis::Finisher, Some(self.settings.participation_basis), TeamTotalsMessage::ParticipationBasisSelected),
+        ];
+
+        column2 = column2.push(participation_column).push(vertical_space().height(10));
+
+        let display_precision = text_input("", &self.display_precision).on_input(TeamTotalsMessage::DisplayPrecision);
+        let rounding_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Decimal Places Shown"),
+            vertical_space().height(1),
+            display_precision,
+            vertical_space().height(5),
+            text("Rounding"),
+            vertical_space().height(1),
+            radio("Round Half Up", RoundingMode::HalfUp, Some(self.settings.rounding_mode), TeamTotalsMessage::RoundingModeSelected),
+            radio("Round Half to Even", RoundingMode::HalfEven, Some(self.settings.rounding_mode), TeamTotalsMessage::RoundingModeSelected),
+            radio("Round Down", RoundingMode::Down, Some(self.settings.rounding_mode), TeamTotalsMessage::RoundingModeSelected),
+            radio("Round Up", RoundingMode::Up, Some(self.settings.rounding_mode), TeamTotalsMessage::RoundingModeSelected),
+        ];
+
+        column2 = column2.push(rounding_column).push(vertical_space().height(10));
+
+        let cross_club_credit_column: widget::Column<'_, TeamTotalsMessage> = column![
+            text("Partners From Different Clubs"),
+            vertical_space().height(1),
+            radio("Split Points Evenly", CrossClubCredit::SplitEvenly, Some(self.settings.cross_club_credit), TeamTotalsMessage::CrossClubCreditSelected),
+            radio("Award Full Points to Each Club", CrossClubCredit::FullCreditToEach, Some(self.settings.cross_club_credit), TeamTotalsMessage::CrossClubCreditSelected),
+            radio("Award Points to the First Listed Club", CrossClubCredit::FirstListedClub, Some(self.settings.cross_club_credit), TeamTotalsMessage::CrossClubCreditSelected),
+        ];
+
+        column2 = column2.push(cross_club_credit_column).push(vertical_space().height(10));
+
+        if self.settings.points_system == PointsSystemKind::EventRules {
+            column2 = column2.push(self.points_rules_view()).push(vertical_space().height(10));
+        }
+
+        if self.settings.points_system == PointsSystemKind::Formula {
+            column2 = column2.push(self.points_formula_view()).push(vertical_space().height(10));
+        }
+
+        // Event rules may read sheets of the spreadsheet and fall back to the single points table.
+        if matches!(self.settings.points_system, PointsSystemKind::FieldSizeChart | PointsSystemKind::EventRules) {
             let use_scoring_system_spreadsheet: Button<TeamTotalsMessage> = Button::new(
                 Text::new(
                     if self.settings.scoring_system_file_name.is_none() {
@@ -855,8 +1824,21 @@ impl TeamTotalsGui {
                         "Remove Scoring System Spreadsheet"
                     })).on_press(TeamTotalsMessage::FindSpreadsheetDirectory);
 
-            column2 = column2.push(use_scoring_system_spreadsheet);
-        } else {
+            column2 = column2.push(use_scoring_system_spreadsheet).push(vertical_space().height(10));
+
+            let chart_resolution_column: widget::Column<'_, TeamTotalsMessage> = column![
+                text("Field Sizes Missing From the Chart"),
+                vertical_space().height(1),
+                radio("Use the Nearest Smaller Column", ChartResolution::NearestSmaller, Some(self.settings.chart_resolution), TeamTotalsMessage::ChartResolutionSelected),
+                radio("Use the Largest Column", ChartResolution::Largest, Some(self.settings.chart_resolution), TeamTotalsMessage::ChartResolutionSelected),
+                radio("Extrapolate From the Nearest Columns", ChartResolution::Extrapolate, Some(self.settings.chart_resolution), TeamTotalsMessage::ChartResolutionSelected),
+                radio("Refuse to Tabulate", ChartResolution::Refuse, Some(self.settings.chart_resolution), TeamTotalsMessage::ChartResolutionSelected),
+            ];
+
+            column2 = column2.push(chart_resolution_column).push(vertical_space().height(10));
+        }
+
+        if matches!(self.settings.points_system, PointsSystemKind::FlatTable | PointsSystemKind::EventRules) {
             column2 = column2.push(text("Points For Each Placement"));
 
             let points_for_each_placement: Element<_> =
@@ -905,17 +1887,26 @@ impl TeamTotalsGui {
         let mut club_points_column: Column<'_, TeamTotalsMessage, Theme, Renderer> = Column::new();
         club_points_column = club_points_column.push(main_button);
         club_points_column = club_points_column.push(vertical_space().height(10));
-        club_points_column = club_points_column.push(row![
+        let participation = awards_participation_points(&self.settings);
+        let mut header_row = row![
             text("#").width(iced::Length::Fixed(PLACEMENT_WIDTH)).align_x(Alignment::Center),
             text("Club").width(iced::Length::FillPortion(3)).align_x(Alignment::Center),
             text("IJS").width(iced::Length::Fill).align_x(Alignment::Center),
             text("6.0").width(iced::Length::Fill).align_x(Alignment::Center),
-            text("Point Total").width(iced::Length::Fill).align_x(Alignment::Center)
-        ]);
+        ];
+        if participation {
+            header_row = header_row.push(text("Participation").width(iced::Length::Fill).align_x(Alignment::Center));
+        }
+        header_row = header_row.push(text("Point Total").width(iced::Length::Fill).align_x(Alignment::Center));
+        let disciplines = subtotal_disciplines(&self.club_points, &self.settings);
+        for discipline in &disciplines {
+            header_row = header_row.push(text(discipline.get_name()).width(iced::Length::Fill).align_x(Alignment::Center));
+        }
+        club_points_column = club_points_column.push(header_row);
         club_points_column = club_points_column.push(vertical_space().height(10));
         let mut placements_column = Column::new();
-        for i in 1..=self.club_points.len() {
-            placements_column = placements_column.push(text_input("", format!("{}", i).as_str()).on_input(NoneInput).width(iced::Length::Fixed(PLACEMENT_WIDTH)));
+        for rank in shared_ranks(&self.club_points, &self.settings.standings_tie_breakers) {
+            placements_column = placements_column.push(text_input("", format!("{}", rank).as_str()).on_input(NoneInput).width(iced::Length::Fixed(PLACEMENT_WIDTH)));
         }
         let club_edit_column: Element<_> =
             keyed_column(
@@ -962,10 +1953,40 @@ impl TeamTotalsGui {
 
         let mut club_points_total_column = Column::new();
         for club_point in &self.club_points {
-            club_points_total_column = club_points_total_column.push(text_input("", format!("{}", club_point.calc_total()).as_str()).on_input(NoneInput));
+            club_points_total_column = club_points_total_column.push(text_input("", format!("{}", display_points(club_point.calc_total(), &self.settings)).as_str()).on_input(NoneInput));
+        }
+
+        let mut edit_row = row![placements_column, club_edit_column, club_points_ijs_edit_column, club_points_60_edit_column];
+        if participation {
+            let club_points_participation_edit_column: Element<_> =
+                keyed_column(
+                    self.club_points_participation_edits
+                        .iter()
+                        .enumerate()
+                        .map(|(i, points_field)| {
+                            (
+                                points_field.index,
+                                points_field.view(i).map(move |message| {
+                                    TeamTotalsMessage::ClubPointsParticipationEdited(i, message)
+                                }),
+                            )
+                        }),
+                ).into();
+            edit_row = edit_row.push(club_points_participation_edit_column);
+        }
+
+        edit_row = edit_row.push(club_points_total_column);
+
+        // Discipline subtotals are only shown. Edits to the IJS and 6.0 points change the total alone.
+        for discipline in disciplines {
+            let mut club_points_discipline_column = Column::new();
+            for club_point in &self.club_points {
+                club_points_discipline_column = club_points_discipline_column.push(text_input("", format!("{}", display_points(club_point.points_discipline(discipline), &self.settings)).as_str()).on_input(NoneInput));
+            }
+            edit_row = edit_row.push(club_points_discipline_column);
         }
 
-        club_points_column = club_points_column.push(row![placements_column, club_edit_column, club_points_ijs_edit_column, club_points_60_edit_column, club_points_total_column]);
+        club_points_column = club_points_column.push(edit_row);
         container(club_points_column).center_x(iced::Length::Fill).align_y(Vertical::Top).height(iced::Length::Fill).into()
     }
 